@@ -1,5 +1,6 @@
-use crate::types::{FileType, ProjectType};
+use crate::types::{DatabaseType, FileType, FrontendType, ProjectType, ServerType};
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(
@@ -10,11 +11,27 @@ use clap::{Parser, Subcommand};
 Nebula - Outil de productivité pour projets Rust
 
 Exemples:
-  Créer un projet API : nebula new mon-projet --type api
+  Créer un projet API : nebula new mon-projet --type api --database sqlite --server both
+  Créer un projet web  : nebula new mon-site --type web --frontend yew
   Générer une entité   : nebula generate entity -e User -f "name:String|required,email:String|unique" -r "posts:has_many:Post"
+  Générer une ressource: nebula scaffold Post -f "title:String|required" -r "author:belongs_to:User"
+  Générer une migration: nebula generate migration create_users
+  Depuis un schéma      : nebula generate --from-schema
+  Supprimer une entité  : nebula destroy entity Post
+  Depuis un autre dossier : nebula -C ./mon-projet generate handler User
+  Prévisualiser          : nebula generate entity Post -f "title:String" --dry-run
 "#
 )]
 pub struct Cli {
+    /// Run as if nebula was started in this directory
+    #[arg(short = 'C', long = "project-dir", global = true)]
+    pub project_dir: Option<PathBuf>,
+    /// Print the files that would be created, modified or overwritten, without writing them
+    #[arg(long = "dry-run", global = true)]
+    pub dry_run: bool,
+    /// Overwrite existing files without asking
+    #[arg(long = "force", global = true)]
+    pub force: bool,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -23,10 +40,23 @@ pub struct Cli {
 pub enum Commands {
     #[command(alias = "n")]
     New(NewArgs),
-    #[command(alias = "g")]
+    /// Generate an entity with its migration, handler, routes and resolver
+    #[command(alias = "s")]
+    Scaffold(ScaffoldArgs),
+    #[command(alias = "g", args_conflicts_with_subcommands = true)]
     Generate {
         #[command(subcommand)]
-        opts: GenerateArgs,
+        opts: Option<GenerateArgs>,
+        /// Generate the enums and entities of a schema file, nebula.schema.toml by default,
+        /// writing nothing if one of them fails
+        #[arg(long = "from-schema", value_name = "FILE")]
+        from_schema: Option<Option<PathBuf>>,
+    },
+    /// Remove what was generated, writing a migration to drop its tables
+    #[command(alias = "d")]
+    Destroy {
+        #[command(subcommand)]
+        opts: DestroyArgs,
     },
 }
 
@@ -35,6 +65,26 @@ pub struct NewArgs {
     pub name: String,
     #[arg(short = 't', long = "type", value_parser = clap::value_parser!(ProjectType))]
     pub opt: Option<ProjectType>,
+    #[arg(short = 'd', long = "database", value_parser = clap::value_parser!(DatabaseType))]
+    pub database: Option<DatabaseType>,
+    #[arg(short = 's', long = "server", value_parser = clap::value_parser!(ServerType))]
+    pub server: Option<ServerType>,
+    #[arg(short = 'f', long = "frontend", value_parser = clap::value_parser!(FrontendType))]
+    pub frontend: Option<FrontendType>,
+}
+
+#[derive(Parser)]
+pub struct ScaffoldArgs {
+    pub name: String,
+    #[arg(long = "fields", short = 'f')]
+    pub fields: Option<String>,
+    #[arg(long = "relations", short = 'r')]
+    pub relations: Option<String>,
+    /// Generate the missing side of relations without asking
+    #[arg(long = "inverse")]
+    pub inverse: bool,
+    #[arg(long = "prefix", short = 'p', default_value = "/api/v1")]
+    pub prefix: String,
 }
 
 #[derive(Subcommand)]
@@ -46,7 +96,30 @@ pub enum GenerateArgs {
         fields: Option<String>,
         #[arg(long = "relations", short = 'r')]
         relations: Option<String>,
+        /// Generate the missing side of relations without asking
+        #[arg(long = "inverse")]
+        inverse: bool,
+    },
+    Enum {
+        name: String,
+        #[arg(required = true)]
+        variants: Vec<String>,
     },
     #[command(alias = "h")]
     Handler { name: String },
+    #[command(alias = "m")]
+    Migration { name: String },
+    #[command(alias = "r")]
+    Resolver { name: String },
+    Routes {
+        name: String,
+        #[arg(long = "prefix", short = 'p', default_value = "/api/v1")]
+        prefix: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DestroyArgs {
+    #[command(alias = "e")]
+    Entity { name: String },
 }
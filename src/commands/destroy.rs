@@ -0,0 +1,46 @@
+use crate::cli::DestroyArgs;
+use crate::generators::api::remove::EntityRemover;
+use crate::utils::{changes, errors::Error, prompt};
+use std::io::IsTerminal;
+
+pub struct DestroyCommand;
+
+impl DestroyCommand {
+    /// Removes an entity after asking, unless `--force` or `--dry-run` is
+    /// given. Nothing is removed unless every step succeeds.
+    pub fn run(args: DestroyArgs) -> Result<(), Error> {
+        match args {
+            DestroyArgs::Entity { name } => {
+                if !Self::confirm(&name)? {
+                    println!("⏭️  {} was not removed", name);
+                    return Ok(());
+                }
+                let failure = format!("{} was not removed", name);
+                let Some(written) =
+                    changes::transaction(&failure, || EntityRemover::new(name.clone()).remove())?
+                else {
+                    return Ok(());
+                };
+                println!("\n✅ Destroyed {} ({} files)\n", name, written.len());
+                changes::print_files(&written);
+                Ok(())
+            }
+        }
+    }
+
+    fn confirm(name: &str) -> Result<bool, Error> {
+        if changes::is_dry_run() || changes::is_forced() {
+            return Ok(true);
+        }
+        if !std::io::stdin().is_terminal() {
+            return Err(Error::InvalidOptions(format!(
+                "Utilisez --force pour supprimer {} sans confirmation",
+                name
+            )));
+        }
+        prompt::ask_removal_confirmation(&format!(
+            "Supprimer {} et les fichiers générés pour cette entité ?",
+            name
+        ))
+    }
+}
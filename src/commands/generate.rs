@@ -1,6 +1,11 @@
 use crate::cli::GenerateArgs;
-use crate::generators::api::entity::EntityGenerator;
+use crate::generators::api::{
+    entity::EntityGenerator, enums::EnumGenerator, field::split_fields, handler::HandlerGenerator,
+    migration::MigrationGenerator, resolver::ResolverGenerator, routes::RoutesGenerator,
+    schema::SchemaGenerator,
+};
 use crate::utils::errors::Error;
+use std::path::PathBuf;
 
 pub struct GenerateCommand;
 
@@ -11,13 +16,28 @@ impl GenerateCommand {
                 name,
                 fields,
                 relations,
+                inverse,
             } => {
-                let parsed_fields = fields.map(|f| f.split(',').map(|s| s.to_string()).collect());
-                let parsed_relations =
-                    relations.map(|r| r.split(',').map(|s| s.to_string()).collect());
-                EntityGenerator::new(name, parsed_fields, parsed_relations).generate()
+                let parsed_fields = fields.as_deref().map(split_fields);
+                let parsed_relations = relations.as_deref().map(split_fields);
+                EntityGenerator::new(name, parsed_fields, parsed_relations, inverse).generate()
             }
-            GenerateArgs::Handler { name } => unimplemented!(),
+            GenerateArgs::Enum { name, variants } => EnumGenerator::new(name, variants).generate(),
+            GenerateArgs::Handler { name } => HandlerGenerator::new(name).generate(),
+            GenerateArgs::Migration { name } => MigrationGenerator::new(name).generate(),
+            GenerateArgs::Resolver { name } => ResolverGenerator::new(name).generate(),
+            GenerateArgs::Routes { name, prefix } => RoutesGenerator::new(name, prefix).generate(),
+        }
+    }
+
+    /// `nebula generate --from-schema [FILE]`, the only way to run `generate`
+    /// without a subcommand.
+    pub fn from_schema(path: Option<Option<PathBuf>>) -> Result<(), Error> {
+        match path {
+            Some(path) => SchemaGenerator::new(path).generate(),
+            None => Err(Error::InvalidOptions(
+                "Indiquez l'élément à générer ou --from-schema".into(),
+            )),
         }
     }
 }
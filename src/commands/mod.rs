@@ -1,2 +1,4 @@
 pub mod new;
-pub mod generate;
\ No newline at end of file
+pub mod generate;
+pub mod scaffold;
+pub mod destroy;
\ No newline at end of file
@@ -1,12 +1,11 @@
-use std::fs;
 use std::path::Path;
 
 use crate::cli::NewArgs;
-use crate::generators::{api::ApiGenerator, web::WebGenerator};
+use crate::config::PathsConfig;
+use crate::generators::{api::ApiGenerator, web::WebGenerator, workspace::WorkspaceGenerator};
 use crate::template;
-use crate::types::ProjectType;
+use crate::types::{DatabaseType, FrontendType, ProjectType, ServerType};
 use crate::utils::errors::Error;
-use crate::utils::file::init_file_from_template;
 use crate::utils::{file, prompt};
 
 pub struct NewCommand;
@@ -20,46 +19,74 @@ impl NewCommand {
             None => prompt::ask_project_type()?,
         };
 
+        let database = match (args.database, project_type) {
+            (Some(database), _) => database,
+            (None, ProjectType::Web) => DatabaseType::Postgresql,
+            (None, _) => prompt::ask_database_type()?,
+        };
+        let server_type = match (args.server, project_type) {
+            (Some(server_type), _) => server_type,
+            (None, ProjectType::Web) => ServerType::Rest,
+            (None, _) => prompt::ask_server_type()?,
+        };
+
+        let frontend = match (args.frontend, project_type) {
+            (Some(frontend), _) => frontend,
+            (None, ProjectType::Api) => FrontendType::Leptos,
+            (None, _) => prompt::ask_frontend_type()?,
+        };
+
         let project_dir = format!("./{}", &project_name);
+        let env_file = format!("{}/.env", &project_dir);
+
+        // The frontend reads its dev server address from .env
+        file::create_dir_if_not_exists(&project_dir)?;
+        Self::generate_env(&project_dir, database)?;
 
         match project_type {
             ProjectType::Web => {
-                WebGenerator::generate(&project_dir)?;
+                WebGenerator::new(&project_name, &project_dir, &env_file, frontend, false)
+                    .generate()?;
             }
             ProjectType::Api => {
-                ApiGenerator::new(&project_dir).generate()?;
+                ApiGenerator::new(&project_dir, database, server_type, "./migrations", false)
+                    .generate()?;
+                Self::generate_migrations_dir(&project_dir, database)?;
             }
             ProjectType::Full => {
                 let web_dir = format!("{}/web", &project_dir);
                 let api_dir = format!("{}/api", project_dir);
 
-                file::create_dir_if_not_exists(&project_dir)?;
                 file::create_dir_if_not_exists(&api_dir)?;
                 file::create_dir_if_not_exists(&web_dir)?;
 
-                ApiGenerator::new(&api_dir).generate()?;
+                ApiGenerator::new(&api_dir, database, server_type, "../migrations", true)
+                    .generate()?;
+                WebGenerator::new(&project_name, &web_dir, &env_file, frontend, true)
+                    .generate()?;
+                WorkspaceGenerator::new(&project_dir).generate()?;
+                Self::generate_migrations_dir(&project_dir, database)?;
             }
         }
 
-        Self::generate_env(&project_dir)?;
         Self::generate_gitignore(&project_dir)?;
-        Self::generate_nebula_config(&project_dir, project_type)?;
-        Self::generate_readme(&project_dir, &project_name)?;
+        Self::generate_nebula_config(&project_dir, project_type, database, server_type, frontend)?;
+        Self::generate_readme(&project_dir, &project_name, database)?;
 
         println!("Projet créé avec succès !");
         Ok(())
     }
 
-    fn generate_env(dir: &str) -> Result<(), Error> {
+    fn generate_env(dir: &str, database: DatabaseType) -> Result<(), Error> {
         let project_path = Path::new(dir);
         let project_name = project_path
             .file_name()
             .and_then(|name| name.to_str())
             .ok_or_else(|| Error::InvalidPath)?;
-        let content = template::get_env_template(project_name);
+        let content = template::get_env_template(project_name, database)?;
         let env_path = project_path.join(".env");
 
-        fs::write(&env_path, &content).map_err(Error::FileSystem)?;
+        file::write(&env_path, &content)?;
 
         println!("✅ Generated environment files");
         Ok(())
@@ -67,42 +94,64 @@ impl NewCommand {
 
     fn generate_gitignore(dir: &str) -> Result<(), Error> {
         let project_path = Path::new(dir);
-        let content = template::get_gitignore_template();
-        let env_path = project_path.join(".env");
+        let content = template::get_gitignore_template()?;
+        let gitignore_path = project_path.join(".gitignore");
 
-        fs::write(&env_path, &content).map_err(Error::FileSystem)?;
+        file::write(&gitignore_path, &content)?;
 
-        println!("✅ Generated environment files");
+        println!("✅ Generated .gitignore file");
         Ok(())
     }
 
-    fn generate_nebula_config(dir: &str, project_type: ProjectType) -> Result<(), Error> {
+    fn generate_nebula_config(
+        dir: &str,
+        project_type: ProjectType,
+        database: DatabaseType,
+        server_type: ServerType,
+        frontend: FrontendType,
+    ) -> Result<(), Error> {
         let project_path = Path::new(dir);
         let project_name = project_path
             .file_name()
             .and_then(|name| name.to_str())
             .ok_or_else(|| Error::InvalidPath)?;
 
+        let paths = PathsConfig::for_project_type(project_type);
         let content = template::get_nebula_template(
             project_name,
             project_type.as_str(),
-            "postgresql",
-            "rest",
-        );
+            database,
+            server_type.as_str(),
+            frontend.as_str(),
+            paths.api.as_deref().unwrap_or_default(),
+            paths.web.as_deref().unwrap_or_default(),
+        )?;
 
         let config_path = project_path.join("nebula.config.toml");
-        fs::write(&config_path, &content).map_err(Error::FileSystem)?;
+        file::write(&config_path, &content)?;
 
         println!("✅ Generated nebula configuration file");
         Ok(())
     }
 
-    fn generate_readme(dir: &str, project_name: &str) -> Result<(), Error> {
+    fn generate_migrations_dir(dir: &str, database: DatabaseType) -> Result<(), Error> {
+        if !database.is_sql() {
+            return Ok(());
+        }
+
+        // sqlx::migrate! needs the directory to exist at compile time
+        file::create_file_in_dir(&format!("{}/migrations", dir), ".gitkeep", "")?;
+
+        println!("✅ Generated migrations directory");
+        Ok(())
+    }
+
+    fn generate_readme(dir: &str, project_name: &str, database: DatabaseType) -> Result<(), Error> {
         let project_path = Path::new(dir);
-        let content = template::get_readme_template(project_name);
+        let content = template::get_readme_template(project_name, database)?;
         let readme_path = project_path.join("README.md");
 
-        fs::write(&readme_path, &content).map_err(Error::FileSystem)?;
+        file::write(&readme_path, &content)?;
 
         println!("✅ Generated README file");
         Ok(())
@@ -0,0 +1,46 @@
+use crate::cli::ScaffoldArgs;
+use crate::config::NebulaConfig;
+use crate::generators::api::{
+    entity::EntityGenerator, field::split_fields, handler::HandlerGenerator,
+    resolver::ResolverGenerator, routes::RoutesGenerator,
+};
+use crate::utils::{changes, errors::Error};
+
+pub struct ScaffoldCommand;
+
+impl ScaffoldCommand {
+    /// Generates the entity and its migration, then the handler and routes
+    /// or the resolver the server type calls for. Nothing is written unless
+    /// every step succeeds.
+    pub fn run(args: ScaffoldArgs) -> Result<(), Error> {
+        let config = NebulaConfig::load()?;
+        let failure = format!("{} was not scaffolded", args.name);
+        let Some(written) = changes::transaction(&failure, || Self::generate(&config, &args))?
+        else {
+            return Ok(());
+        };
+        println!("\n✅ Scaffolded {} ({} files)\n", args.name, written.len());
+        changes::print_files(&written);
+        Ok(())
+    }
+
+    fn generate(config: &NebulaConfig, args: &ScaffoldArgs) -> Result<(), Error> {
+        EntityGenerator::new(
+            args.name.clone(),
+            args.fields.as_deref().map(split_fields),
+            args.relations.as_deref().map(split_fields),
+            args.inverse,
+        )
+        .generate()?;
+
+        let server_type = config.project.server_type;
+        if server_type.has_rest() {
+            HandlerGenerator::new(args.name.clone()).generate()?;
+            RoutesGenerator::new(args.name.clone(), args.prefix.clone()).generate()?;
+        }
+        if server_type.has_graphql() {
+            ResolverGenerator::new(args.name.clone()).generate()?;
+        }
+        Ok(())
+    }
+}
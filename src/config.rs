@@ -0,0 +1,225 @@
+use crate::types::{DatabaseType, ProjectType, ServerType};
+use crate::utils::errors::Error;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = "nebula.config.toml";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NebulaConfig {
+    #[serde(skip)]
+    pub root: PathBuf,
+    pub project: ProjectConfig,
+    #[serde(default)]
+    pub paths: PathsConfig,
+    #[serde(default)]
+    pub environment: BTreeMap<String, String>,
+    #[serde(default)]
+    pub deployment: DeploymentConfig,
+    #[serde(default)]
+    pub security: SecurityConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProjectConfig {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub project_type: ProjectType,
+    #[serde(default = "default_database")]
+    pub database: DatabaseType,
+    #[serde(default = "default_server_type")]
+    pub server_type: ServerType,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PathsConfig {
+    pub src: Option<String>,
+    pub api: Option<String>,
+    pub web: Option<String>,
+    pub migrations: Option<String>,
+    pub templates: Option<String>,
+    #[serde(rename = "static")]
+    pub static_files: Option<String>,
+    pub tests: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DeploymentConfig {
+    pub provider: Option<String>,
+    pub container_name: Option<String>,
+    #[serde(default)]
+    pub docker_compose: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SecurityConfig {
+    #[serde(default)]
+    pub cors_enabled: bool,
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+}
+
+fn default_database() -> DatabaseType {
+    DatabaseType::Postgresql
+}
+
+fn default_server_type() -> ServerType {
+    ServerType::Rest
+}
+
+impl PathsConfig {
+    /// Default layout of a freshly generated project of the given type.
+    pub fn for_project_type(project_type: ProjectType) -> Self {
+        let (api, web) = match project_type {
+            ProjectType::Api => ("src", "web"),
+            ProjectType::Web => ("api/src", "."),
+            ProjectType::Full => ("api/src", "web"),
+        };
+        Self {
+            src: Some("src".into()),
+            api: Some(api.into()),
+            web: Some(web.into()),
+            migrations: Some("migrations".into()),
+            templates: Some("templates".into()),
+            static_files: Some("static".into()),
+            tests: Some("tests".into()),
+        }
+    }
+}
+
+impl NebulaConfig {
+    /// Loads the configuration of the project containing the current
+    /// directory, searching parent directories like cargo does.
+    pub fn load() -> Result<Self, Error> {
+        let cwd = std::env::current_dir()?;
+        let root = Self::find_root(&cwd).ok_or(Error::NotNebulaProject)?;
+        Self::load_from(root)
+    }
+
+    pub fn find_root(start: &Path) -> Option<&Path> {
+        start
+            .ancestors()
+            .find(|dir| dir.join(CONFIG_FILE).is_file())
+    }
+
+    pub fn load_from(root: &Path) -> Result<Self, Error> {
+        let config_path = root.join(CONFIG_FILE);
+        if !config_path.exists() {
+            return Err(Error::NotNebulaProject);
+        }
+
+        let content = std::fs::read_to_string(&config_path)?;
+        let mut config: Self = toml::from_str(&content)
+            .map_err(|e| Error::ConfigError(format!("{}: {}", CONFIG_FILE, e)))?;
+        config.root = root.to_path_buf();
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.project.name.trim().is_empty() {
+            return Err(Error::ConfigError(
+                "[project] name must not be empty".into(),
+            ));
+        }
+
+        let paths = [
+            ("src", &self.paths.src),
+            ("api", &self.paths.api),
+            ("web", &self.paths.web),
+            ("migrations", &self.paths.migrations),
+            ("templates", &self.paths.templates),
+            ("static", &self.paths.static_files),
+            ("tests", &self.paths.tests),
+        ];
+        for (key, path) in paths {
+            match path.as_deref() {
+                Some(p) if p.trim().is_empty() => {
+                    return Err(Error::ConfigError(format!(
+                        "[paths] {} must not be empty",
+                        key
+                    )));
+                }
+                Some(p) if Path::new(p).is_absolute() => {
+                    return Err(Error::ConfigError(format!(
+                        "[paths] {} must be relative to the project root, got '{}'",
+                        key, p
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        for key in ["DB_PORT", "SERVER_PORT", "APP_PORT"] {
+            if let Some(port) = self.environment.get(key) {
+                if port.parse::<u16>().is_err() {
+                    return Err(Error::ConfigError(format!(
+                        "[environment] {} must be a valid port number, got '{}'",
+                        key, port
+                    )));
+                }
+            }
+        }
+
+        if self.deployment.docker_compose && self.deployment.provider.as_deref() != Some("docker") {
+            return Err(Error::ConfigError(
+                "[deployment] docker_compose requires provider = \"docker\"".into(),
+            ));
+        }
+
+        if let Some(name) = &self.deployment.container_name {
+            let valid = name.starts_with(|c: char| c.is_ascii_alphanumeric())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c));
+            if !valid {
+                return Err(Error::ConfigError(format!(
+                    "[deployment] container_name '{}' is not a valid container name",
+                    name
+                )));
+            }
+        }
+
+        if self.security.cors_enabled
+            && self
+                .security
+                .allowed_origins
+                .iter()
+                .any(|o| o.trim().is_empty())
+        {
+            return Err(Error::ConfigError(
+                "[security] allowed_origins must not contain empty values".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn default_paths(&self) -> PathsConfig {
+        PathsConfig::for_project_type(self.project.project_type)
+    }
+
+    fn resolve(&self, path: &Option<String>, default: Option<String>) -> PathBuf {
+        self.root.join(path.clone().or(default).unwrap_or_default())
+    }
+
+    /// Source directory of the API crate, where `main.rs` and entities live.
+    pub fn api_src_path(&self) -> Result<PathBuf, Error> {
+        if self.project.project_type == ProjectType::Web {
+            return Err(Error::ConfigError(
+                "a web project has no API, set [project] type to \"api\" or \"full\"".into(),
+            ));
+        }
+        Ok(self.resolve(&self.paths.api, self.default_paths().api))
+    }
+
+    pub fn migrations_path(&self) -> PathBuf {
+        self.resolve(&self.paths.migrations, self.default_paths().migrations)
+    }
+
+    /// Directory whose `.tpl` files override the built-in templates.
+    pub fn templates_path(&self) -> PathBuf {
+        self.resolve(&self.paths.templates, self.default_paths().templates)
+    }
+}
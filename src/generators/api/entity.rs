@@ -1,223 +1,428 @@
+use super::enums;
+use super::field::{self, EntityField, EnumType, Relation};
+use super::many_to_many::ManyToManyGenerator;
+use super::migration::MigrationGenerator;
+use super::relations::{self, MissingSide};
 use crate::{
+    config::NebulaConfig,
     template,
-    types::ProjectType,
+    types::DatabaseType,
     utils::{self, errors::Error},
 };
-use std::{fmt, iter, path::PathBuf};
-
-#[derive(Debug, Clone)]
-enum Relation {
-    HasOne(String),
-    HasMany(String),
-    BelongsTo(String),
-}
+use std::io::IsTerminal;
 
 trait Validatable {
     fn validate(&self) -> Result<(), Error>;
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct FieldRules {
-    required: bool,
-    min_length: Option<usize>,
-    max_length: Option<usize>,
-    pattern: Option<String>,
-    min: Option<String>,
-    max: Option<String>,
-    unique: bool,
-}
-
-#[derive(Debug, Clone, Default)]
-pub struct FieldValidator {
-    unique: bool,
-    custom_rules: Vec<String>,
-}
-
-pub struct EntityField {
-    name: String,
-    field_type: String,
-    validators: FieldValidator,
-    relation: Option<Relation>,
-}
-
-impl std::fmt::Display for EntityField {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}: {}", self.name, self.field_type)?;
-        if !self.validators.custom_rules.is_empty() {
-            write!(f, " [{}]", self.validators.custom_rules.join(", "))?;
-        }
-        Ok(())
-    }
-}
-
 pub struct EntityGenerator {
     name: String,
     fields: Option<Vec<String>>,
     relations: Option<Vec<String>>,
-}
-
-impl EntityField {
-    fn new(raw_field: &str) -> Result<Self, Error> {
-        let mut parts = raw_field.splitn(2, '|');
-        let name_type = parts
-            .next()
-            .ok_or(Error::InvalidOptions("Empty field".into()))?;
-        let (name, field_type) = if name_type.contains(':') {
-            let mut parts = name_type.splitn(2, ':');
-            (parts.next().unwrap().trim(), parts.next().unwrap().trim())
-        } else {
-            let mut parts = name_type.splitn(2, '|');
-            (
-                parts.next().unwrap().trim(),
-                parts.next().unwrap_or("String").trim(),
-            )
-        };
-        let validators = parts
-            .next()
-            .unwrap_or_default()
-            .split('|')
-            .collect::<Vec<_>>();
-        let relation = match field_type {
-            s if s.starts_with("has_one:") => Some(Relation::HasOne(s[8..].to_string())),
-            s if s.starts_with("has_many:") => Some(Relation::HasMany(s[9..].to_string())),
-            s if s.starts_with("belongs_to:") => Some(Relation::BelongsTo(s[11..].to_string())),
-            _ => None,
-        };
-        Ok(Self {
-            name: name.to_string(),
-            field_type: field_type.to_string(),
-            validators: FieldValidator {
-                unique: validators.contains(&"unique"),
-                custom_rules: validators
-                    .iter()
-                    .filter(|&&v| v != "unique")
-                    .map(|s| s.to_string())
-                    .collect(),
-            },
-            relation,
-        })
-    }
+    /// Generate the missing sides of relations without asking.
+    inverse: bool,
+    check_relations: bool,
+    /// Leave the many-to-many relations to `generate_many_to_many`, once the
+    /// tables their join tables reference exist.
+    defer_many_to_many: bool,
 }
 
 impl EntityGenerator {
-    pub fn new(name: String, fields: Option<Vec<String>>, relations: Option<Vec<String>>) -> Self {
+    pub fn new(
+        name: String,
+        fields: Option<Vec<String>>,
+        relations: Option<Vec<String>>,
+        inverse: bool,
+    ) -> Self {
         Self {
             name,
             fields,
             relations,
+            inverse,
+            check_relations: true,
+            defer_many_to_many: false,
+        }
+    }
+
+    /// An entity of a schema, whose relations were checked with the other
+    /// entities of the schema.
+    pub fn declared(name: String, fields: Vec<String>, relations: Vec<String>) -> Self {
+        Self {
+            name,
+            fields: Some(fields),
+            relations: Some(relations),
+            inverse: false,
+            check_relations: false,
+            defer_many_to_many: true,
+        }
+    }
+
+    /// A missing target entity, generated with the relations back to the
+    /// entity that points to it.
+    fn missing_target(name: String, inverses: Vec<String>) -> Self {
+        Self {
+            name,
+            fields: None,
+            relations: Some(inverses),
+            inverse: false,
+            check_relations: false,
+            defer_many_to_many: false,
         }
     }
 
     pub fn generate(&self) -> Result<(), Error> {
         self.validate()?;
-        utils::tools::check_is_nebula_project()?;
-        let fields = self.parse_fields()?;
-        let relations = self.parse_relations()?;
-        let content = self.generate_content(&fields, &relations)?;
-        self.write_entity_file(&content)?;
-        self.update_mod_file()?;
+        let config = NebulaConfig::load()?;
+        let shared_enums = enums::load_shared(&config)?;
+        let fields = Self::parse(&self.fields, &self.name, &shared_enums)?;
+        let relations = Self::parse(&self.relations, &self.name, &shared_enums)?;
+        if !Self::has_inputs(&config, fields.iter().chain(relations.iter())) {
+            return Err(Error::ValidationError(format!(
+                "{} has no fields, GraphQL needs at least one in its inputs: pass them with -f",
+                self.name
+            )));
+        }
+        let database = config.project.database;
+        for field in fields.iter().filter(|f| f.enum_type.is_none()) {
+            if let Some(signed) = database.signed_replacement(&field.field_type) {
+                return Err(Error::InvalidFieldFormat(format!(
+                    "{}: {} has no unsigned integers, use {} instead",
+                    field.name,
+                    database.as_str(),
+                    signed
+                )));
+            }
+        }
+        let many_to_many = self.many_to_many(&relations);
+        for relation in &many_to_many {
+            relation.validate(&config)?;
+            // A schema generates the `through` entities before the relations
+            if !self.defer_many_to_many {
+                relation.check_through(&config)?;
+            }
+        }
+        let missing = self.missing_sides(&config, &fields, &relations)?;
+        for side in &missing {
+            if let MissingSide::Entity {
+                name,
+                inverses,
+                first: true,
+            } = side
+            {
+                Self::missing_target(name.clone(), inverses.clone()).generate()?;
+            }
+        }
+        let content = self.generate_content(&config, &fields, &relations)?;
+        let repository = self.generate_repository(config.project.database, &fields, &relations)?;
+        self.write_entity_file(&config, &content, &repository)?;
+        self.update_mod_file(&config)?;
+        self.update_dependencies(&config, &fields)?;
+        self.generate_migration(&config, &fields, &relations)?;
+        for side in missing {
+            match side {
+                MissingSide::Entity {
+                    name,
+                    inverses,
+                    first: false,
+                } => Self::missing_target(name, inverses).generate()?,
+                MissingSide::Inverse { entity, inverse } => {
+                    relations::add_inverse(&config, &entity, &inverse)?
+                }
+                MissingSide::Entity { .. } => {}
+            }
+        }
+        // After the inverses, whose names the accessors of the other side
+        // must not take
+        if !self.defer_many_to_many {
+            for relation in &many_to_many {
+                relation.generate(&config)?;
+            }
+        }
+        for name in self.related_entities(&fields, &relations) {
+            relations::write_loaders(&config, &name)?;
+        }
         Ok(())
     }
 
-    fn parse_fields(&self) -> Result<Vec<EntityField>, Error> {
-        self.fields
-            .as_ref()
-            .map(|f| f.iter().map(|s| EntityField::new(s)).collect())
-            .unwrap_or_else(|| Ok(Vec::new()))
+    /// Generates the many-to-many relations left out by `generate`.
+    pub fn generate_many_to_many(&self) -> Result<(), Error> {
+        let config = NebulaConfig::load()?;
+        let shared_enums = enums::load_shared(&config)?;
+        let relations = Self::parse(&self.relations, &self.name, &shared_enums)?;
+        for relation in self.many_to_many(&relations) {
+            relation.generate(&config)?;
+        }
+        Ok(())
     }
 
-    fn parse_relations(&self) -> Result<Vec<EntityField>, Error> {
-        self.relations
-            .as_ref()
-            .map(|r| r.iter().map(|s| EntityField::new(s)).collect())
+    fn parse(
+        raw: &Option<Vec<String>>,
+        entity: &str,
+        shared_enums: &[EnumType],
+    ) -> Result<Vec<EntityField>, Error> {
+        raw.as_ref()
+            .map(|r| {
+                r.iter()
+                    .map(|s| EntityField::new(s, entity, shared_enums))
+                    .collect()
+            })
             .unwrap_or_else(|| Ok(Vec::new()))
     }
 
     fn generate_content(
         &self,
+        config: &NebulaConfig,
         fields: &[EntityField],
         relations: &[EntityField],
     ) -> Result<String, Error> {
-        let all_fields = fields.iter().chain(relations.iter());
-        let fields_code = all_fields
-            .map(|f| f.to_rust_code())
-            .collect::<Vec<_>>()
-            .join("\n");
-        Ok(template::get_entity_template(&self.name, &fields_code))
-    }
-
-    fn get_base_path(&self) -> Result<PathBuf, Error> {
-        match utils::tools::get_project_config()? {
-            ProjectType::Api => Ok(PathBuf::from("src/")),
-            ProjectType::Full => Ok(PathBuf::from("api/src/")),
-            _ => Err(Error::InvalidOptions("Invalid project type".into())),
-        }
-    }
-
-    fn write_entity_file(&self, content: &str) -> Result<(), Error> {
-        let base_path = self.get_base_path()?;
-        let entity_dir = base_path.join(&self.name.to_lowercase());
-        std::fs::create_dir_all(&entity_dir)?;
-        std::fs::write(entity_dir.join("entity.rs"), content)?;
-        std::fs::write(
-            entity_dir.join("mod.rs"),
-            "mod entity;\npub use entity::*;\n",
-        )?;
-        Ok(())
+        let specs = fields
+            .iter()
+            .chain(relations.iter())
+            .filter_map(EntityField::relation_spec)
+            .collect::<Vec<_>>();
+        let enums = Self::enums(fields)
+            .iter()
+            .map(EnumType::to_context)
+            .collect::<Vec<_>>();
+        let collections = field::collections(fields);
+        // Relations stored in other tables are loaded by `relations.rs`
+        let fields = fields
+            .iter()
+            .chain(relations.iter())
+            .filter_map(EntityField::to_context)
+            .collect::<Vec<_>>();
+
+        template::get_entity_template(
+            &self.name,
+            &fields,
+            &enums,
+            &collections,
+            &specs,
+            config.project.database,
+            config.project.server_type.has_graphql(),
+        )
     }
 
-    fn update_mod_file(&self) -> Result<(), Error> {
-        let mod_path = self.get_base_path()?.join("mod.rs");
-        let mod_line = format!("pub mod {};", self.name.to_lowercase());
-        let mut content = std::fs::read_to_string(&mod_path).unwrap_or_default();
-        if !content.contains(&mod_line) {
-            content.push_str(&format!("\n{}", mod_line));
-            std::fs::write(mod_path, content)?;
+    fn generate_repository(
+        &self,
+        database: DatabaseType,
+        fields: &[EntityField],
+        relations: &[EntityField],
+    ) -> Result<String, Error> {
+        let table = utils::tools::pluralize(&self.name.to_lowercase());
+        if !database.is_sql() {
+            return template::get_mongodb_repository_template(&self.name, &table);
         }
-        Ok(())
+
+        let columns = fields
+            .iter()
+            .chain(relations.iter())
+            .filter_map(EntityField::to_context)
+            .map(|field| template::ColumnContext {
+                name: field.name,
+                json: field.json,
+                optional: field.optional,
+            })
+            .collect::<Vec<_>>();
+        template::get_sql_repository_template(&self.name, &table, &columns, database)
     }
-}
 
-impl Validatable for EntityGenerator {
-    fn validate(&self) -> Result<(), Error> {
-        if self.name.is_empty() || !self.name.chars().all(|c| c.is_alphanumeric()) {
-            return Err(Error::ValidationError("Invalid entity name".into()));
+    fn write_entity_file(
+        &self,
+        config: &NebulaConfig,
+        content: &str,
+        repository: &str,
+    ) -> Result<(), Error> {
+        let base_path = config.api_src_path()?;
+        let entity_dir = base_path.join(self.name.to_lowercase());
+        utils::file::write(&entity_dir.join("entity.rs"), content)?;
+        utils::file::write(&entity_dir.join("repository.rs"), repository)?;
+        // mod.rs also lists the handler, routes and resolver generated later
+        let mod_path = entity_dir.join("mod.rs");
+        for declaration in ["mod entity;", "mod repository;", "pub use entity::*;"] {
+            utils::file::add_mod_declaration(&mod_path, declaration)?;
         }
         Ok(())
     }
-}
 
-impl FieldValidator {
-    fn to_validation_attributes(&self) -> Vec<String> {
-        let mut attrs = Vec::new();
-        if self.unique {
-            attrs.push("#[validate(custom = \"validate_unique\")]".into());
+    fn generate_migration(
+        &self,
+        config: &NebulaConfig,
+        fields: &[EntityField],
+        relations: &[EntityField],
+    ) -> Result<(), Error> {
+        let database = config.project.database;
+        let table = utils::tools::pluralize(&self.name.to_lowercase());
+        let columns = fields
+            .iter()
+            .chain(relations.iter())
+            .filter_map(|f| f.to_sql_column(database))
+            .collect::<Vec<_>>();
+        MigrationGenerator::generate_for_table(config, &table, &columns, &Self::enums(fields))
+    }
+
+    /// The missing sides of the relations to generate, asking about each one
+    /// unless `--inverse` was given. A missing target entity that is not
+    /// generated fails, since the entity could not compile without it.
+    fn missing_sides(
+        &self,
+        config: &NebulaConfig,
+        fields: &[EntityField],
+        relations: &[EntityField],
+    ) -> Result<Vec<MissingSide>, Error> {
+        if !self.check_relations {
+            return Ok(Vec::new());
+        }
+        let declared = fields
+            .iter()
+            .chain(relations.iter())
+            .filter(|f| f.relation.is_some())
+            .collect::<Vec<_>>();
+        let project = relations::scan(config)?
+            .into_iter()
+            .filter(|entity| entity.name != self.name)
+            .collect::<Vec<_>>();
+
+        let mut accepted = Vec::new();
+        for side in relations::check(&self.name, &declared, &project)? {
+            match &side {
+                MissingSide::Entity { name, inverses, .. } => {
+                    let columns = inverses
+                        .iter()
+                        .map(|spec| EntityField::new(spec, name, &[]))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if !Self::has_inputs(config, columns.iter()) {
+                        return Err(Error::InvalidRelationType(format!(
+                            "{} does not exist, generate it first with its fields: nebula generate entity {} -f ...",
+                            name, name
+                        )));
+                    }
+                    let question = if inverses.is_empty() {
+                        format!("L'entité {} n'existe pas, voulez-vous la générer ?", name)
+                    } else {
+                        format!(
+                            "L'entité {} n'existe pas, voulez-vous la générer avec {} ?",
+                            name,
+                            inverses.join(", ")
+                        )
+                    };
+                    if !self.confirm(&question)? {
+                        return Err(Error::InvalidRelationType(format!(
+                            "{} does not exist, generate it first or pass --inverse",
+                            name
+                        )));
+                    }
+                }
+                MissingSide::Inverse { entity, inverse } => {
+                    let question = format!(
+                        "L'entité {} n'a pas de relation vers {}, voulez-vous lui ajouter {} ?",
+                        entity, self.name, inverse
+                    );
+                    if !self.confirm(&question)? {
+                        println!(
+                            "⚠️  {} has no relation back to {}, add {} to it",
+                            entity, self.name, inverse
+                        );
+                        continue;
+                    }
+                }
+            }
+            accepted.push(side);
         }
-        attrs.extend(
-            self.custom_rules
-                .iter()
-                .map(|r| format!("#[validate({})]", r)),
-        );
-        attrs
+        Ok(accepted)
+    }
+
+    /// Whether the inputs of an entity with `fields` have a field, which
+    /// async-graphql requires of an input object.
+    fn has_inputs<'a>(
+        config: &NebulaConfig,
+        mut fields: impl Iterator<Item = &'a EntityField>,
+    ) -> bool {
+        !config.project.server_type.has_graphql() || fields.any(|f| f.to_context().is_some())
+    }
+
+    /// Without a terminal to ask on, nothing is generated.
+    fn confirm(&self, question: &str) -> Result<bool, Error> {
+        if self.inverse {
+            return Ok(true);
+        }
+        if !std::io::stdin().is_terminal() {
+            return Ok(false);
+        }
+        utils::prompt::ask_confirmation(question)
+    }
+
+    fn many_to_many(&self, relations: &[EntityField]) -> Vec<ManyToManyGenerator> {
+        relations
+            .iter()
+            .filter_map(|relation| match &relation.relation {
+                Some(Relation::ManyToMany(target, through)) => Some(ManyToManyGenerator::new(
+                    self.name.clone(),
+                    relation.name.clone(),
+                    target.clone(),
+                    through.clone(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The entity and the entities its relations point to, whose loaders
+    /// depend on its foreign keys.
+    fn related_entities(&self, fields: &[EntityField], relations: &[EntityField]) -> Vec<String> {
+        let mut names = vec![self.name.clone()];
+        for relation in fields
+            .iter()
+            .chain(relations.iter())
+            .filter_map(|f| f.relation.as_ref())
+        {
+            if !names.iter().any(|name| name == relation.target()) {
+                names.push(relation.target().to_string());
+            }
+        }
+        names
+    }
+
+    /// The enums used by `fields`, each once.
+    fn enums(fields: &[EntityField]) -> Vec<EnumType> {
+        let mut enums: Vec<EnumType> = Vec::new();
+        for enum_type in fields.iter().filter_map(|f| f.enum_type.as_ref()) {
+            if !enums.iter().any(|e| e.name == enum_type.name) {
+                enums.push(enum_type.clone());
+            }
+        }
+        enums
+    }
+
+    /// `regex` rules compile their pattern with the regex crate.
+    fn update_dependencies(
+        &self,
+        config: &NebulaConfig,
+        fields: &[EntityField],
+    ) -> Result<(), Error> {
+        if fields.iter().all(|f| f.rules.pattern().is_none()) {
+            return Ok(());
+        }
+        let manifest = config
+            .api_src_path()?
+            .parent()
+            .ok_or(Error::InvalidPath)?
+            .join("Cargo.toml");
+        utils::file::add_dependency(&manifest, "regex", "\"1.11\"")
+    }
+
+    fn update_mod_file(&self, config: &NebulaConfig) -> Result<(), Error> {
+        let main_path = config.api_src_path()?.join("main.rs");
+        let mod_line = format!("pub mod {};", self.name.to_lowercase());
+        utils::file::add_mod_declaration(&main_path, &mod_line)
     }
 }
 
-impl EntityField {
-    fn to_rust_code(&self) -> String {
-        let mut code = Vec::new();
-        code.extend(
-            self.validators
-                .to_validation_attributes()
-                .into_iter()
-                .map(|a| format!("    {}", a)),
-        );
-        let field_type = match &self.relation {
-            Some(Relation::HasOne(t)) => format!("Option<{}>", t),
-            Some(Relation::HasMany(t)) => format!("Vec<{}>", t),
-            Some(Relation::BelongsTo(t)) => t.clone(),
-            None => self.field_type.clone(),
-        };
-        code.push(format!("    pub {}: {},", self.name, field_type));
-        code.join("\n")
+impl Validatable for EntityGenerator {
+    fn validate(&self) -> Result<(), Error> {
+        if self.name.is_empty() || !self.name.chars().all(|c| c.is_alphanumeric()) {
+            return Err(Error::ValidationError("Invalid entity name".into()));
+        }
+        Ok(())
     }
 }
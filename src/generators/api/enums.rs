@@ -0,0 +1,67 @@
+use super::field::EnumType;
+use super::migration::MigrationGenerator;
+use crate::{
+    config::NebulaConfig,
+    template,
+    utils::{self, errors::Error},
+};
+use convert_case::{Case, Casing};
+
+pub struct EnumGenerator {
+    name: String,
+    variants: Vec<String>,
+}
+
+impl EnumGenerator {
+    pub fn new(name: String, variants: Vec<String>) -> Self {
+        Self { name, variants }
+    }
+
+    pub fn generate(&self) -> Result<(), Error> {
+        let enum_type = EnumType::new(&self.name, self.variants.clone(), true)
+            .map_err(Error::ValidationError)?;
+        let config = NebulaConfig::load()?;
+        let src = config.api_src_path()?;
+        let module = enum_type.name.to_case(Case::Snake);
+        let path = src.join("enums").join(format!("{}.rs", module));
+        if utils::file::exists(&path) {
+            return Err(Error::ElementAlreadyExists(format!(
+                "enum {}",
+                enum_type.name
+            )));
+        }
+
+        let content = template::get_enum_template(
+            &enum_type.to_context(),
+            config.project.database,
+            config.project.server_type.has_graphql(),
+        )?;
+        utils::file::write(&path, &content)?;
+        let mod_path = src.join("enums").join("mod.rs");
+        utils::file::add_mod_declaration(&mod_path, &format!("mod {};", module))?;
+        utils::file::add_mod_declaration(&mod_path, &format!("pub use {}::*;", module))?;
+        utils::file::add_mod_declaration(&src.join("main.rs"), "pub mod enums;")?;
+        MigrationGenerator::generate_for_enum(&config, &enum_type)?;
+
+        println!("✅ Generated enum {}", enum_type.name);
+        Ok(())
+    }
+}
+
+/// The enums of `src/enums/`, which entity fields may use as types.
+pub fn load_shared(config: &NebulaConfig) -> Result<Vec<EnumType>, Error> {
+    let dir = config.api_src_path()?.join("enums");
+    let mut enums = Vec::new();
+    for path in utils::file::list_dir(&dir)? {
+        if path.file_name().is_some_and(|name| name == "mod.rs") {
+            continue;
+        }
+        let content = utils::file::read_to_string(&path)?;
+        if let Some((name, variants)) = utils::source::find_enum(&content) {
+            if let Ok(enum_type) = EnumType::new(&name, variants, true) {
+                enums.push(enum_type);
+            }
+        }
+    }
+    Ok(enums)
+}
@@ -0,0 +1,1360 @@
+//! Parser for the fields and relations given to `nebula generate entity`:
+//!
+//! ```text
+//! field     = name [ ":" type ] [ "?" ] [ "=" default ] { "|" rule }
+//! type      = relation ":" name | "belongs_to:" name [ "?" ]
+//!           | "many_to_many:" name [ ":through:" name ] | enum | rust_type
+//! relation  = "has_one" | "has_many"
+//! enum      = "enum" "(" variant { "," variant } ")"
+//! rust_type = path [ "<" rust_type { "," rust_type } ">" ]
+//!           | "(" [ rust_type { "," rust_type } ] ")"
+//!           | "[" rust_type ";" number "]"
+//! path      = name { "::" name }
+//! ```
+//!
+//! A field without a type is a `String`, a `?` makes it an `Option` and a
+//! nullable column. An inline enum is named after the entity and the field,
+//! `PostStatus` for `status` in `Post`, and a type naming an enum of
+//! `src/enums/` uses that enum. A `belongs_to` relation is a foreign key,
+//! `author_id` for `author`, nullable with a `?`. A `many_to_many` relation is
+//! stored in a join table, the table of the `through` entity when there is
+//! one. Inside a rule or a default, `\|`, `\,` and `\\` stand for the
+//! character itself.
+
+use convert_case::{Case, Casing};
+
+use crate::{
+    template,
+    types::{base_type, DatabaseType},
+    utils::{self, errors::Error},
+};
+
+const RELATION_KINDS: [&str; 4] = ["has_one", "has_many", "belongs_to", "many_to_many"];
+
+#[derive(Debug, Clone)]
+pub enum Relation {
+    HasOne(String),
+    HasMany(String),
+    BelongsTo(String),
+    /// Target and optional `through` entity, whose table is the join table.
+    ManyToMany(String, Option<String>),
+}
+
+impl Relation {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Relation::HasOne(_) => "has_one",
+            Relation::HasMany(_) => "has_many",
+            Relation::BelongsTo(_) => "belongs_to",
+            Relation::ManyToMany(..) => "many_to_many",
+        }
+    }
+
+    pub fn target(&self) -> &str {
+        match self {
+            Relation::HasOne(target)
+            | Relation::HasMany(target)
+            | Relation::BelongsTo(target)
+            | Relation::ManyToMany(target, _) => target,
+        }
+    }
+}
+
+const RULES: [&str; 9] = [
+    "required",
+    "unique",
+    "email",
+    "url",
+    "min_length",
+    "max_length",
+    "min",
+    "max",
+    "regex",
+];
+
+const COLLECTION_TYPES: [&str; 6] = [
+    "Vec", "VecDeque", "HashMap", "HashSet", "BTreeMap", "BTreeSet",
+];
+
+const INTEGER_TYPES: [&str; 12] = [
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+];
+
+/// Validation rules of a field, from `|email|min_length=3|max_length=100`.
+#[derive(Debug, Clone, Default)]
+pub struct FieldRules {
+    required: bool,
+    email: bool,
+    url: bool,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<String>,
+    min: Option<String>,
+    max: Option<String>,
+    unique: bool,
+}
+
+/// Default value of a field, from `count:i32=0` or `title:String="Untitled"`.
+#[derive(Debug, Clone)]
+pub enum DefaultValue {
+    Number(String),
+    Bool(bool),
+    Text(String),
+    /// Rust path and stored value of an enum variant.
+    Variant(String, String),
+}
+
+/// A Rust enum stored as text, from `status:enum(Draft,Published)` or
+/// `nebula generate enum Status Draft Published`.
+#[derive(Debug, Clone)]
+pub struct EnumType {
+    pub name: String,
+    pub variants: Vec<String>,
+    /// Declared in `src/enums/` rather than in the entity file.
+    pub shared: bool,
+}
+
+pub struct EntityField {
+    pub name: String,
+    /// Declared type, without the `Option` of an optional field.
+    pub field_type: String,
+    pub optional: bool,
+    pub default: Option<DefaultValue>,
+    pub rules: FieldRules,
+    pub relation: Option<Relation>,
+    pub enum_type: Option<EnumType>,
+}
+
+impl std::fmt::Display for EntityField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.rust_type())?;
+        if let Some(default) = &self.default {
+            write!(f, " = {}", default.to_rust())?;
+        }
+        let rules = self.rules.to_strings();
+        if !rules.is_empty() {
+            write!(f, " [{}]", rules.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl EntityField {
+    /// Parses a field of `entity`, where `enums` are the enums of
+    /// `src/enums/` a field type may name.
+    pub fn new(raw_field: &str, entity: &str, enums: &[EnumType]) -> Result<Self, Error> {
+        let tokens = tokenize(raw_field)?;
+        Parser {
+            input: raw_field,
+            tokens,
+            pos: 0,
+            entity,
+            enums,
+        }
+        .field()
+    }
+
+    /// Type of the field in the entity struct, or of the records loaded for a
+    /// relation without a column.
+    pub fn rust_type(&self) -> String {
+        match &self.relation {
+            Some(Relation::HasOne(t)) => format!("Option<{}>", t),
+            Some(Relation::HasMany(t) | Relation::ManyToMany(t, _)) => format!("Vec<{}>", t),
+            Some(Relation::BelongsTo(_)) if self.optional => "Option<i32>".to_string(),
+            Some(Relation::BelongsTo(_)) => "i32".to_string(),
+            None if self.optional => format!("Option<{}>", self.field_type),
+            None => self.field_type.clone(),
+        }
+    }
+
+    /// Column of the field: its name, the foreign key of a `belongs_to`, and
+    /// nothing for the relations stored in other tables.
+    pub fn column(&self) -> Option<String> {
+        match &self.relation {
+            None => Some(self.name.clone()),
+            Some(Relation::BelongsTo(_)) => Some(format!("{}_id", self.name)),
+            Some(_) => None,
+        }
+    }
+
+    /// The relation in the syntax of `-r`, as in `posts:has_many:Post`.
+    pub fn relation_spec(&self) -> Option<String> {
+        let relation = self.relation.as_ref()?;
+        let mut spec = format!("{}:{}:{}", self.name, relation.kind(), relation.target());
+        match relation {
+            Relation::ManyToMany(_, Some(through)) => {
+                spec.push_str(&format!(":through:{}", through))
+            }
+            Relation::BelongsTo(_) if self.optional => spec.push('?'),
+            _ => {}
+        }
+        Some(spec)
+    }
+
+    /// The field as a column of the entity struct, `None` for the relations
+    /// stored in other tables.
+    pub fn to_context(&self) -> Option<template::FieldContext> {
+        let inner_type = match &self.relation {
+            None => self.field_type.clone(),
+            Some(Relation::BelongsTo(_)) => "i32".to_string(),
+            Some(_) => return None,
+        };
+        Some(template::FieldContext {
+            name: self.column()?,
+            field_type: self.rust_type(),
+            inner_type,
+            optional: self.optional,
+            default: self.default.as_ref().map(DefaultValue::to_rust),
+            validations: self.rules.to_validations(self.optional, &self.name),
+            pattern: self.rules.pattern.clone(),
+            json: self.relation.is_none()
+                && self.enum_type.is_none()
+                && DatabaseType::is_json_type(&self.field_type),
+        })
+    }
+
+    pub fn to_sql_column(&self, database: DatabaseType) -> Option<String> {
+        match &self.relation {
+            Some(Relation::BelongsTo(target)) => Some(format!(
+                "{}_id {}{} REFERENCES {}(id)",
+                self.name,
+                database.sql_type("i32"),
+                if self.optional { "" } else { " NOT NULL" },
+                utils::tools::pluralize(&target.to_lowercase())
+            )),
+            Some(_) => None,
+            None => {
+                let sql_type = match &self.enum_type {
+                    Some(enum_type) => enum_type.sql_type(database),
+                    None => database.sql_type(&self.field_type).to_string(),
+                };
+                let mut column = format!("{} {}", self.name, sql_type);
+                if !self.optional || self.rules.required {
+                    column.push_str(" NOT NULL");
+                }
+                if let Some(default) = &self.default {
+                    column.push_str(&format!(" DEFAULT {}", default.to_sql(database)));
+                }
+                if self.rules.unique {
+                    column.push_str(" UNIQUE");
+                }
+                // SQLite has no enum type, the values are checked instead
+                if let Some(enum_type) = &self.enum_type {
+                    if database == DatabaseType::Sqlite {
+                        column.push_str(&format!(
+                            " CHECK ({} IN ({}))",
+                            self.name,
+                            enum_type.sql_values()
+                        ));
+                    }
+                }
+                Some(column)
+            }
+        }
+    }
+}
+
+impl EnumType {
+    /// Checks that the enum and its variants are PascalCase identifiers.
+    pub fn new(name: &str, variants: Vec<String>, shared: bool) -> Result<Self, String> {
+        if !is_pascal_case(name) {
+            return Err(format!("'{}' is not a PascalCase enum name", name));
+        }
+        if variants.is_empty() {
+            return Err(format!("enum {} needs at least one variant", name));
+        }
+        for (i, variant) in variants.iter().enumerate() {
+            check_variant(variant)?;
+            if variants[..i].contains(variant) {
+                return Err(format!("duplicate variant '{}'", variant));
+            }
+        }
+        Ok(Self {
+            name: name.to_string(),
+            variants,
+            shared,
+        })
+    }
+
+    /// The default `value` of a field of this enum, a variant name.
+    fn default(&self, value: &str) -> Result<DefaultValue, String> {
+        match self.variants.iter().find(|variant| *variant == value) {
+            Some(variant) => Ok(DefaultValue::Variant(
+                format!("{}::{}", self.name, variant),
+                stored_value(variant),
+            )),
+            None => Err(format!(
+                "'{}' is not a variant of {}, expected one of {}",
+                value,
+                self.name,
+                self.variants.join(", ")
+            )),
+        }
+    }
+
+    /// Name of the PostgreSQL type.
+    pub fn type_name(&self) -> String {
+        self.name.to_case(Case::Snake)
+    }
+
+    /// Values stored in the database, in snake_case as serde sends them.
+    pub fn values(&self) -> Vec<String> {
+        self.variants
+            .iter()
+            .map(|variant| stored_value(variant))
+            .collect()
+    }
+
+    fn sql_values(&self) -> String {
+        self.values()
+            .iter()
+            .map(|value| format!("'{}'", value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn sql_type(&self, database: DatabaseType) -> String {
+        match database {
+            DatabaseType::Postgresql => self.type_name(),
+            DatabaseType::Mysql | DatabaseType::Mariadb => format!("ENUM({})", self.sql_values()),
+            _ => "TEXT".to_string(),
+        }
+    }
+
+    pub fn to_context(&self) -> template::EnumContext {
+        template::EnumContext {
+            name: self.name.clone(),
+            type_name: self.type_name(),
+            variants: self.variants.clone(),
+            values: self.values(),
+            shared: self.shared,
+        }
+    }
+}
+
+impl FieldRules {
+    pub fn pattern(&self) -> Option<&str> {
+        self.pattern.as_deref()
+    }
+
+    /// Adds `rule` to the rules of a field of type `field_type`, failing
+    /// with the reason it does not apply.
+    fn add(&mut self, rule: &str, field_type: &str) -> Result<(), String> {
+        let (name, value) = match rule.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (rule, None),
+        };
+        if !RULES.contains(&name) {
+            return Err(format!(
+                "unknown rule '{}', expected one of {}",
+                name,
+                RULES.join(", ")
+            ));
+        }
+        if self
+            .to_strings()
+            .iter()
+            .any(|r| r.split('=').next() == Some(name))
+        {
+            return Err(format!("duplicate rule '{}'", name));
+        }
+
+        let base_type = base_type(field_type);
+        let applies = match name {
+            "required" | "unique" => true,
+            "email" | "url" | "regex" => is_string(base_type),
+            "min_length" | "max_length" => is_string(base_type) || is_collection(base_type),
+            _ => is_number(base_type),
+        };
+        if !applies {
+            let expected = match name {
+                "email" | "url" | "regex" => "strings",
+                "min_length" | "max_length" => "strings and collections",
+                _ => "numbers",
+            };
+            return Err(format!(
+                "'{}' applies to {}, not to '{}'",
+                name, expected, field_type
+            ));
+        }
+
+        match (name, value) {
+            ("required" | "unique" | "email" | "url", Some(_)) => {
+                return Err(format!("'{}' takes no value", name))
+            }
+            ("required", None) => self.required = true,
+            ("unique", None) => self.unique = true,
+            ("email", None) => self.email = true,
+            ("url", None) => self.url = true,
+            (_, None) | (_, Some("")) => {
+                return Err(format!("'{}' needs a value, as in '{}=...'", name, name))
+            }
+            ("min_length" | "max_length", Some(value)) => {
+                let length = value.parse::<usize>().map_err(|_| {
+                    format!("'{}' must be a positive integer, got '{}'", name, value)
+                })?;
+                if name == "min_length" {
+                    self.min_length = Some(length);
+                } else {
+                    self.max_length = Some(length);
+                }
+            }
+            ("min" | "max", Some(value)) => {
+                let valid = if INTEGER_TYPES.contains(&base_type) {
+                    value.parse::<i128>().is_ok()
+                } else {
+                    value.parse::<f64>().is_ok_and(f64::is_finite)
+                };
+                if !valid {
+                    return Err(format!(
+                        "'{}' must be a valid {} value, got '{}'",
+                        name, base_type, value
+                    ));
+                }
+                let value = if INTEGER_TYPES.contains(&base_type) {
+                    value.to_string()
+                } else {
+                    float_literal(value)
+                };
+                if name == "min" {
+                    self.min = Some(value);
+                } else {
+                    self.max = Some(value);
+                }
+            }
+            (_, Some(pattern)) => self.pattern = Some(pattern.to_string()),
+        }
+
+        if let (Some(min), Some(max)) = (self.min_length, self.max_length) {
+            if min > max {
+                return Err(format!(
+                    "min_length {} is greater than max_length {}",
+                    min, max
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (&self.min, &self.max) {
+            // Integers are compared exactly, beyond the precision of a float
+            let greater = match (min.parse::<i128>(), max.parse::<i128>()) {
+                (Ok(min), Ok(max)) => min > max,
+                _ => min.parse::<f64>().ok() > max.parse::<f64>().ok(),
+            };
+            if greater {
+                return Err(format!("min {} is greater than max {}", min, max));
+            }
+        }
+        Ok(())
+    }
+
+    fn to_strings(&self) -> Vec<String> {
+        let flags = [
+            ("required", self.required),
+            ("unique", self.unique),
+            ("email", self.email),
+            ("url", self.url),
+        ];
+        let values = [
+            ("min_length", self.min_length.map(|v| v.to_string())),
+            ("max_length", self.max_length.map(|v| v.to_string())),
+            ("min", self.min.clone()),
+            ("max", self.max.clone()),
+            ("regex", self.pattern.clone()),
+        ];
+
+        flags
+            .into_iter()
+            .filter(|(_, set)| *set)
+            .map(|(name, _)| name.to_string())
+            .chain(
+                values
+                    .into_iter()
+                    .filter_map(|(name, value)| Some(format!("{}={}", name, value?))),
+            )
+            .collect()
+    }
+
+    /// Arguments of the `#[validate(...)]` attribute of the field, in the
+    /// syntax of validator 0.20. `unique` is enforced by the database.
+    fn to_validations(&self, optional: bool, field_name: &str) -> Vec<String> {
+        let mut validations = Vec::new();
+        // A field that is not an Option always has a value
+        if self.required && optional {
+            validations.push("required".to_string());
+        }
+        if self.email {
+            validations.push("email".to_string());
+        }
+        if self.url {
+            validations.push("url".to_string());
+        }
+        if let Some(bounds) = bounds(self.min_length, self.max_length) {
+            validations.push(format!("length({})", bounds));
+        }
+        if let Some(bounds) = bounds(self.min.as_ref(), self.max.as_ref()) {
+            validations.push(format!("range({})", bounds));
+        }
+        if self.pattern.is_some() {
+            validations.push(format!(
+                "regex(path = *{}_REGEX)",
+                field_name.to_case(Case::UpperSnake)
+            ));
+        }
+        validations
+    }
+}
+
+impl DefaultValue {
+    /// Checks that `value` is a literal of the field type. Strings may be
+    /// quoted.
+    fn parse(value: &str, field_type: &str) -> Result<Self, String> {
+        let base_type = base_type(field_type);
+        if INTEGER_TYPES.contains(&base_type) {
+            return match value.parse::<i128>() {
+                Ok(_) => Ok(DefaultValue::Number(value.to_string())),
+                Err(_) => Err(format!("'{}' is not a valid {}", value, base_type)),
+            };
+        }
+        match base_type {
+            "f32" | "f64" => match value.parse::<f64>() {
+                Ok(number) if number.is_finite() => Ok(DefaultValue::Number(float_literal(value))),
+                _ => Err(format!("'{}' is not a valid {}", value, base_type)),
+            },
+            "bool" => match value {
+                "true" => Ok(DefaultValue::Bool(true)),
+                "false" => Ok(DefaultValue::Bool(false)),
+                _ => Err(format!(
+                    "'{}' is not a valid bool, expected true or false",
+                    value
+                )),
+            },
+            "String" => {
+                let text = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .unwrap_or(value);
+                Ok(DefaultValue::Text(text.to_string()))
+            }
+            _ => Err(format!(
+                "defaults apply to strings, numbers and booleans, not to '{}'",
+                field_type
+            )),
+        }
+    }
+
+    fn to_rust(&self) -> String {
+        match self {
+            DefaultValue::Number(number) => number.clone(),
+            DefaultValue::Bool(value) => value.to_string(),
+            DefaultValue::Text(text) => format!("{:?}.to_string()", text),
+            DefaultValue::Variant(path, _) => path.clone(),
+        }
+    }
+
+    fn to_sql(&self, database: DatabaseType) -> String {
+        match self {
+            DefaultValue::Number(number) => number.clone(),
+            DefaultValue::Bool(value) if database == DatabaseType::Sqlite => {
+                (*value as u8).to_string()
+            }
+            DefaultValue::Bool(value) => value.to_string().to_uppercase(),
+            DefaultValue::Text(text) => format!("'{}'", text.replace('\'', "''")),
+            DefaultValue::Variant(_, value) => format!("'{}'", value),
+        }
+    }
+}
+
+fn bounds<T: std::fmt::Display>(min: Option<T>, max: Option<T>) -> Option<String> {
+    let bounds = [("min", min), ("max", max)]
+        .into_iter()
+        .filter_map(|(name, value)| Some(format!("{} = {}", name, value?)))
+        .collect::<Vec<_>>();
+    (!bounds.is_empty()).then(|| bounds.join(", "))
+}
+
+/// `value` as a float literal, which needs a decimal point in Rust.
+fn float_literal(value: &str) -> String {
+    let mut number = value.to_string();
+    if !number.contains(['.', 'e', 'E']) {
+        number.push_str(".0");
+    }
+    number
+}
+
+fn is_pascal_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase()) && name.chars().all(char::is_alphanumeric)
+}
+
+/// serde and sqlx disagree on the snake_case of acronyms, `HTTPError` must be
+/// written `HttpError`.
+fn check_variant(variant: &str) -> Result<(), String> {
+    let acronym = variant
+        .as_bytes()
+        .windows(2)
+        .any(|pair| pair.iter().all(u8::is_ascii_uppercase));
+    if !is_pascal_case(variant) || !variant.is_ascii() || acronym {
+        return Err(format!("'{}' is not a PascalCase variant", variant));
+    }
+    Ok(())
+}
+
+/// The value serde's `rename_all = "snake_case"` gives to `variant`.
+fn stored_value(variant: &str) -> String {
+    let mut value = String::new();
+    for (i, c) in variant.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            value.push('_');
+        }
+        value.push(c.to_ascii_lowercase());
+    }
+    value
+}
+
+fn is_string(base_type: &str) -> bool {
+    matches!(base_type, "String" | "str")
+}
+
+fn is_collection(base_type: &str) -> bool {
+    COLLECTION_TYPES.contains(&base_type)
+}
+
+fn is_number(base_type: &str) -> bool {
+    INTEGER_TYPES.contains(&base_type) || matches!(base_type, "f32" | "f64")
+}
+
+/// Collections of `std::collections` the fields name without their path,
+/// imported by the entity file.
+pub fn collections(fields: &[EntityField]) -> Vec<&'static str> {
+    let paths = fields
+        .iter()
+        .filter(|field| field.relation.is_none())
+        .flat_map(|field| {
+            field
+                .field_type
+                .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        })
+        .collect::<Vec<_>>();
+    COLLECTION_TYPES
+        .into_iter()
+        .filter(|&collection| collection != "Vec" && paths.contains(&collection))
+        .collect()
+}
+
+/// Splits a comma separated list of fields. Commas inside brackets, as in
+/// `HashMap<String, i32>`, and escaped commas do not separate fields.
+pub fn split_fields(input: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut in_rules = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+                continue;
+            }
+            ',' if depth == 0 => {
+                fields.push(std::mem::take(&mut current));
+                in_rules = false;
+                continue;
+            }
+            '|' | '=' if depth == 0 => in_rules = true,
+            // Rules and defaults may compare values, only types use angle brackets
+            '<' if !in_rules => depth += 1,
+            '>' if !in_rules => depth = depth.saturating_sub(1),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        current.push(c);
+    }
+    fields.push(current);
+
+    fields
+        .into_iter()
+        .map(|field| field.trim().to_string())
+        .filter(|field| !field.is_empty())
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Symbol(char),
+    PathSeparator,
+    Pipe,
+    Rule(String),
+    Value(String),
+    End,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// Position of the token in the input, in characters.
+    column: usize,
+}
+
+impl TokenKind {
+    fn describe(&self) -> String {
+        match self {
+            TokenKind::Ident(ident) => format!("'{}'", ident),
+            TokenKind::Symbol(c) => format!("'{}'", c),
+            TokenKind::PathSeparator => "'::'".into(),
+            TokenKind::Pipe => "'|'".into(),
+            TokenKind::Rule(text) | TokenKind::Value(text) => format!("'{}'", text),
+            TokenKind::End => "end of input".into(),
+        }
+    }
+}
+
+/// Formats `message` followed by the input with a caret under `column`.
+fn pointer(input: &str, column: usize, message: &str) -> String {
+    format!(
+        "{} (column {})\n  {}\n  {}^",
+        message,
+        column + 1,
+        input,
+        " ".repeat(column)
+    )
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i;
+        let kind = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '|' => {
+                i += 1;
+                tokens.push(Token {
+                    kind: TokenKind::Pipe,
+                    column,
+                });
+                let (rule, end) = read_text(&chars, i);
+                tokens.push(Token {
+                    kind: TokenKind::Rule(rule),
+                    column: i,
+                });
+                i = end;
+                continue;
+            }
+            '=' => {
+                i += 1;
+                tokens.push(Token {
+                    kind: TokenKind::Symbol('='),
+                    column,
+                });
+                let (value, end) = read_text(&chars, i);
+                let skipped = chars[i..end]
+                    .iter()
+                    .take_while(|c| c.is_whitespace())
+                    .count();
+                tokens.push(Token {
+                    kind: TokenKind::Value(value),
+                    column: i + skipped,
+                });
+                i = end;
+                continue;
+            }
+            ':' if chars.get(i + 1) == Some(&':') => {
+                i += 2;
+                TokenKind::PathSeparator
+            }
+            ':' | '<' | '>' | ',' | '(' | ')' | '[' | ']' | ';' | '?' => {
+                i += 1;
+                TokenKind::Symbol(c)
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                TokenKind::Ident(chars[start..i].iter().collect())
+            }
+            '\\' => {
+                return Err(Error::InvalidFieldFormat(pointer(
+                    input,
+                    column,
+                    "escapes are only allowed in rules and defaults",
+                )))
+            }
+            c => {
+                return Err(Error::InvalidFieldFormat(pointer(
+                    input,
+                    column,
+                    &format!("unexpected character '{}'", c),
+                )))
+            }
+        };
+        tokens.push(Token { kind, column });
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::End,
+        column: chars.len(),
+    });
+    Ok(tokens)
+}
+
+/// Reads a rule or a default up to the next unescaped `|`, returning it
+/// with the escapes resolved and the position of that `|`.
+fn read_text(chars: &[char], start: usize) -> (String, usize) {
+    let mut rule = String::new();
+    let mut i = start;
+
+    while i < chars.len() && chars[i] != '|' {
+        match (chars[i], chars.get(i + 1)) {
+            ('\\', Some(&escaped @ ('|' | ',' | '\\'))) => {
+                rule.push(escaped);
+                i += 2;
+            }
+            (c, _) => {
+                rule.push(c);
+                i += 1;
+            }
+        }
+    }
+    (rule.trim().to_string(), i)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    entity: &'a str,
+    enums: &'a [EnumType],
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn peek_kind_at(&self, offset: usize) -> Option<&TokenKind> {
+        self.tokens.get(self.pos + offset).map(|t| &t.kind)
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        if &self.peek().kind == kind {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error_at(&self, token: &Token, message: &str) -> String {
+        pointer(self.input, token.column, message)
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        let token = self.peek();
+        Error::InvalidFieldFormat(self.error_at(
+            token,
+            &format!("expected {}, found {}", expected, token.kind.describe()),
+        ))
+    }
+
+    /// An identifier: a letter or `_` followed by letters, digits or `_`.
+    fn ident(&mut self) -> Option<String> {
+        match &self.peek().kind {
+            TokenKind::Ident(ident) if !ident.starts_with(|c: char| c.is_numeric()) => {
+                let ident = ident.clone();
+                self.advance();
+                Some(ident)
+            }
+            _ => None,
+        }
+    }
+
+    fn field(&mut self) -> Result<EntityField, Error> {
+        let name = self
+            .ident()
+            .ok_or_else(|| self.unexpected("a field name"))?;
+
+        let typed = self.eat(&TokenKind::Symbol(':'));
+        let mut enum_type = None;
+        let (mut field_type, relation) = if typed && self.at_enum() {
+            let inline = self.enum_type(&name)?;
+            let field_type = inline.name.clone();
+            enum_type = Some(inline);
+            (field_type, None)
+        } else if typed {
+            if matches!(
+                self.peek().kind,
+                TokenKind::Pipe | TokenKind::End | TokenKind::Symbol('?' | '=')
+            ) {
+                return Err(Error::MissingTypForField(self.error_at(
+                    self.peek(),
+                    &format!("expected a type after '{}:'", name),
+                )));
+            }
+            self.field_type()?
+        } else {
+            ("String".to_string(), None)
+        };
+
+        // `Option<T>` and `T?` are the same optional field
+        let mut optional = false;
+        if relation.is_none() {
+            if let Some(inner) = field_type
+                .strip_prefix("Option<")
+                .and_then(|t| t.strip_suffix('>'))
+            {
+                field_type = inner.to_string();
+                optional = true;
+            }
+            if self.peek().kind == TokenKind::Symbol('?') {
+                if optional {
+                    return Err(Error::InvalidFieldFormat(
+                        self.error_at(self.peek(), "the type is already an Option"),
+                    ));
+                }
+                self.advance();
+                optional = true;
+            }
+            if enum_type.is_none() {
+                enum_type = self.enums.iter().find(|e| e.name == field_type).cloned();
+            }
+        } else if matches!(relation, Some(Relation::BelongsTo(_))) {
+            optional = self.eat(&TokenKind::Symbol('?'));
+        }
+
+        let mut default = None;
+        if relation.is_none() && self.eat(&TokenKind::Symbol('=')) {
+            let token = self.advance();
+            let result = match &token.kind {
+                TokenKind::Value(_) if optional => Err("optional fields default to None".into()),
+                TokenKind::Value(value) if !value.is_empty() => match &enum_type {
+                    Some(enum_type) => enum_type.default(value),
+                    None => DefaultValue::parse(value, &field_type),
+                },
+                _ => Err("expected a default value after '='".to_string()),
+            };
+            default =
+                Some(result.map_err(|message| {
+                    Error::InvalidFieldFormat(self.error_at(&token, &message))
+                })?);
+        }
+
+        let mut rules = FieldRules::default();
+        while self.eat(&TokenKind::Pipe) {
+            let token = self.advance();
+            let result = match &token.kind {
+                TokenKind::Rule(_) if relation.is_some() => {
+                    Err("relations take no validation rules".to_string())
+                }
+                TokenKind::Rule(rule) if !rule.is_empty() => rules.add(rule, &field_type),
+                _ => Err("empty rule".to_string()),
+            };
+            result.map_err(|message| Error::InvalidFieldFormat(self.error_at(&token, &message)))?;
+        }
+
+        if self.peek().kind != TokenKind::End {
+            let expected = if !typed {
+                "':', '?', '=' or '|'"
+            } else {
+                "'?', '=', '|' or the end of the field"
+            };
+            return Err(self.unexpected(expected));
+        }
+
+        Ok(EntityField {
+            name,
+            field_type,
+            optional,
+            default,
+            rules,
+            relation,
+            enum_type,
+        })
+    }
+
+    fn at_enum(&self) -> bool {
+        self.peek().kind == TokenKind::Ident("enum".into())
+            && self.peek_kind_at(1) == Some(&TokenKind::Symbol('('))
+    }
+
+    /// `enum(A, B)`, named after the entity and `field`.
+    fn enum_type(&mut self, field: &str) -> Result<EnumType, Error> {
+        let start = self.advance();
+        self.advance();
+
+        let mut variants: Vec<String> = Vec::new();
+        loop {
+            let token = self.peek().clone();
+            let variant = self.ident().ok_or_else(|| self.unexpected("a variant"))?;
+            check_variant(&variant)
+                .map_err(|message| Error::InvalidFieldFormat(self.error_at(&token, &message)))?;
+            if variants.contains(&variant) {
+                return Err(Error::InvalidFieldFormat(
+                    self.error_at(&token, &format!("duplicate variant '{}'", variant)),
+                ));
+            }
+            variants.push(variant);
+            if self.eat(&TokenKind::Symbol(')')) {
+                break;
+            }
+            if !self.eat(&TokenKind::Symbol(',')) {
+                return Err(self.unexpected("',' or ')'"));
+            }
+        }
+
+        let name = format!(
+            "{}{}",
+            self.entity.to_case(Case::Pascal),
+            field.to_case(Case::Pascal)
+        );
+        EnumType::new(&name, variants, false)
+            .map_err(|message| Error::InvalidFieldFormat(self.error_at(&start, &message)))
+    }
+
+    fn field_type(&mut self) -> Result<(String, Option<Relation>), Error> {
+        let kind = match &self.peek().kind {
+            TokenKind::Ident(kind)
+                if RELATION_KINDS.contains(&kind.as_str())
+                    && self.peek_kind_at(1) == Some(&TokenKind::Symbol(':')) =>
+            {
+                kind.clone()
+            }
+            _ => return Ok((self.rust_type()?, None)),
+        };
+        self.advance();
+        self.advance();
+
+        let target = self.ident().ok_or_else(|| {
+            Error::InvalidRelationFormat(self.error_at(
+                self.peek(),
+                &format!("expected the related entity after '{}:'", kind),
+            ))
+        })?;
+        let through = if kind == "many_to_many" && self.eat(&TokenKind::Symbol(':')) {
+            if !self.eat(&TokenKind::Ident("through".into())) {
+                return Err(Error::InvalidRelationFormat(self.error_at(
+                    self.peek(),
+                    &format!("expected 'through:' after '{}:{}:'", kind, target),
+                )));
+            }
+            if !self.eat(&TokenKind::Symbol(':')) {
+                return Err(Error::InvalidRelationFormat(
+                    self.error_at(self.peek(), "expected ':' after 'through'"),
+                ));
+            }
+            Some(self.ident().ok_or_else(|| {
+                Error::InvalidRelationFormat(
+                    self.error_at(self.peek(), "expected the join entity after 'through:'"),
+                )
+            })?)
+        } else {
+            None
+        };
+        let nullable = kind == "belongs_to" && self.peek().kind == TokenKind::Symbol('?');
+        if !nullable && !matches!(self.peek().kind, TokenKind::Pipe | TokenKind::End) {
+            return Err(Error::InvalidRelationFormat(self.error_at(
+                self.peek(),
+                &format!(
+                    "expected '|' or the end of the relation, found {}",
+                    self.peek().kind.describe()
+                ),
+            )));
+        }
+
+        let relation = match kind.as_str() {
+            "has_one" => Relation::HasOne(target.clone()),
+            "has_many" => Relation::HasMany(target.clone()),
+            "many_to_many" => Relation::ManyToMany(target.clone(), through),
+            _ => Relation::BelongsTo(target.clone()),
+        };
+        Ok((format!("{}:{}", kind, target), Some(relation)))
+    }
+
+    fn rust_type(&mut self) -> Result<String, Error> {
+        if self.eat(&TokenKind::Symbol('(')) {
+            let elements = self.type_list(')')?;
+            return Ok(if elements.len() == 1 {
+                format!("({},)", elements[0])
+            } else {
+                format!("({})", elements.join(", "))
+            });
+        }
+
+        if self.eat(&TokenKind::Symbol('[')) {
+            let element = self.rust_type()?;
+            if !self.eat(&TokenKind::Symbol(';')) {
+                return Err(self.unexpected("';' and the length of the array"));
+            }
+            let length = match &self.peek().kind {
+                TokenKind::Ident(length) if length.chars().all(|c| c.is_ascii_digit()) => {
+                    length.clone()
+                }
+                _ => return Err(self.unexpected("the length of the array")),
+            };
+            self.advance();
+            if !self.eat(&TokenKind::Symbol(']')) {
+                return Err(self.unexpected("']'"));
+            }
+            return Ok(format!("[{}; {}]", element, length));
+        }
+
+        let mut path = self.ident().ok_or_else(|| self.unexpected("a type"))?;
+        while self.eat(&TokenKind::PathSeparator) {
+            let segment = self
+                .ident()
+                .ok_or_else(|| self.unexpected("a type after '::'"))?;
+            path.push_str("::");
+            path.push_str(&segment);
+        }
+
+        if self.eat(&TokenKind::Symbol('<')) {
+            if self.peek().kind == TokenKind::Symbol('>') {
+                return Err(self.unexpected("a type"));
+            }
+            let arguments = self.type_list('>')?;
+            path.push_str(&format!("<{}>", arguments.join(", ")));
+        }
+        Ok(path)
+    }
+
+    /// Comma separated types, up to and including `close`.
+    fn type_list(&mut self, close: char) -> Result<Vec<String>, Error> {
+        let mut types = Vec::new();
+        while !self.eat(&TokenKind::Symbol(close)) {
+            types.push(self.rust_type()?);
+            if !self.eat(&TokenKind::Symbol(',')) {
+                if self.eat(&TokenKind::Symbol(close)) {
+                    break;
+                }
+                return Err(self.unexpected(&format!("',' or '{}'", close)));
+            }
+        }
+        Ok(types)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &str) -> EntityField {
+        EntityField::new(raw, "Post", &[]).unwrap_or_else(|e| panic!("{}: {}", raw, e))
+    }
+
+    /// The message of the error and the column its caret points at.
+    fn error(raw: &str) -> (String, usize) {
+        let message = match EntityField::new(raw, "Post", &[]) {
+            Ok(field) => panic!("{} parsed as {}", raw, field),
+            Err(e) => e.to_string(),
+        };
+        let mut lines = message.lines().rev();
+        let caret = lines.next().unwrap();
+        assert_eq!(lines.next().unwrap(), format!("  {}", raw));
+        assert!(caret.ends_with('^'), "{}", message);
+        let column = caret.len() - 3;
+        (message, column)
+    }
+
+    #[test]
+    fn parses_nested_generics() {
+        let field = parse("scores:HashMap<String, Vec<Option<i32>>>");
+        assert_eq!(field.rust_type(), "HashMap<String, Vec<Option<i32>>>");
+        assert!(!field.optional);
+
+        let field = parse("tags:Option<Vec<String>>");
+        assert_eq!(field.field_type, "Vec<String>");
+        assert!(field.optional);
+
+        let field = parse("tags:Vec<String>?");
+        assert_eq!(field.rust_type(), "Option<Vec<String>>");
+
+        assert_eq!(parse("pair:(i32,String)").rust_type(), "(i32, String)");
+        assert_eq!(parse("hash:[u8;32]").rust_type(), "[u8; 32]");
+        assert_eq!(
+            parse("at:chrono::DateTime<chrono::Utc>").rust_type(),
+            "chrono::DateTime<chrono::Utc>"
+        );
+    }
+
+    #[test]
+    fn imports_the_collections_named_without_path() {
+        let fields = [
+            parse("scores:HashMap<String, BTreeSet<i32>>"),
+            parse("queue:std::collections::VecDeque<i32>"),
+            parse("tags:Vec<String>"),
+            parse("posts:has_many:HashSet"),
+        ];
+        assert_eq!(collections(&fields), vec!["HashMap", "BTreeSet"]);
+    }
+
+    #[test]
+    fn stores_path_qualified_types_in_their_column() {
+        let field = parse("at:chrono::DateTime<chrono::Utc>");
+        assert!(!field.to_context().unwrap().json);
+        assert_eq!(
+            field.to_sql_column(DatabaseType::Postgresql).as_deref(),
+            Some("at TIMESTAMPTZ NOT NULL")
+        );
+
+        let field = parse("scores:std::collections::HashMap<String, i32>");
+        assert!(field.to_context().unwrap().json);
+    }
+
+    #[test]
+    fn splits_fields_outside_generics() {
+        assert_eq!(
+            split_fields("scores:HashMap<String, i32>, pair:(i32, i32),n:i32|min=0"),
+            vec![
+                "scores:HashMap<String, i32>",
+                "pair:(i32, i32)",
+                "n:i32|min=0"
+            ]
+        );
+        assert_eq!(
+            split_fields(r"code:String|regex=^a\,b$,n:i32|max=10"),
+            vec![r"code:String|regex=^a\,b$", "n:i32|max=10"]
+        );
+    }
+
+    #[test]
+    fn unescapes_rule_arguments() {
+        assert_eq!(
+            parse(r"code:String|regex=^a\|b$").rules.pattern(),
+            Some("^a|b$")
+        );
+        assert_eq!(
+            parse(r"code:String|regex=^a\,b$").rules.pattern(),
+            Some("^a,b$")
+        );
+        assert_eq!(
+            parse(r"code:String|regex=^\\d+$").rules.pattern(),
+            Some(r"^\d+$")
+        );
+
+        let field = parse(r#"title:String="a\|b"|max_length=10"#);
+        assert!(matches!(&field.default, Some(DefaultValue::Text(t)) if t == "a|b"));
+    }
+
+    #[test]
+    fn writes_float_bounds_as_float_literals() {
+        let field = parse("score:f64|min=0|max=1e3");
+        assert_eq!(field.to_string(), "score: f64 [min=0.0, max=1e3]");
+        assert_eq!(
+            field.rules.to_validations(false, "score"),
+            vec!["range(min = 0.0, max = 1e3)"]
+        );
+
+        let field = parse("count:i32|min=-5|max=10");
+        assert_eq!(field.to_string(), "count: i32 [min=-5, max=10]");
+
+        let (message, _) = error("score:f32|min=2|max=1.5");
+        assert!(
+            message.contains("min 2.0 is greater than max 1.5"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn points_at_the_error_column() {
+        let (message, column) = error("n:i32|min=5|max=1");
+        assert!(
+            message.contains("min 5 is greater than max 1 (column 13)"),
+            "{}",
+            message
+        );
+        assert_eq!(column, 12);
+
+        let (message, column) = error("title:");
+        assert!(message.contains("(column 7)"), "{}", message);
+        assert_eq!(column, 6);
+
+        let (message, column) = error("scores:HashMap<String i32>");
+        assert!(message.contains("(column 23)"), "{}", message);
+        assert_eq!(column, 22);
+
+        let (message, column) = error(r"ti\tle:String");
+        assert!(message.contains("escapes are only allowed"), "{}", message);
+        assert_eq!(column, 2);
+
+        let (message, column) = error("title:Option<String>?");
+        assert!(message.contains("already an Option"), "{}", message);
+        assert_eq!(column, 20);
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        let (message, column) = error("titré:String|nope");
+        assert!(message.contains("(column 14)"), "{}", message);
+        assert_eq!(column, 13);
+    }
+
+    #[test]
+    fn parses_relations() {
+        let field = parse("posts:has_many:Post");
+        assert!(matches!(&field.relation, Some(Relation::HasMany(t)) if t == "Post"));
+        assert_eq!(field.rust_type(), "Vec<Post>");
+        assert_eq!(field.column(), None);
+
+        let field = parse("author:belongs_to:User?");
+        assert!(matches!(&field.relation, Some(Relation::BelongsTo(t)) if t == "User"));
+        assert!(field.optional);
+        assert_eq!(field.rust_type(), "Option<i32>");
+        assert_eq!(field.column().as_deref(), Some("author_id"));
+        assert_eq!(
+            field.relation_spec().as_deref(),
+            Some("author:belongs_to:User?")
+        );
+
+        let field = parse("tags:many_to_many:Tag:through:PostTag");
+        assert!(matches!(
+            &field.relation,
+            Some(Relation::ManyToMany(t, Some(j))) if t == "Tag" && j == "PostTag"
+        ));
+        assert_eq!(
+            field.relation_spec().as_deref(),
+            Some("tags:many_to_many:Tag:through:PostTag")
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_relations() {
+        let (message, column) = error("author:belongs_to:");
+        assert!(
+            message.contains("expected the related entity"),
+            "{}",
+            message
+        );
+        assert_eq!(column, 18);
+
+        let (message, column) = error("tags:many_to_many:Tag:via:PostTag");
+        assert!(message.contains("expected 'through:'"), "{}", message);
+        assert_eq!(column, 22);
+
+        let (message, column) = error("posts:has_many:Post|required");
+        assert!(
+            message.contains("relations take no validation rules"),
+            "{}",
+            message
+        );
+        assert_eq!(column, 20);
+    }
+}
@@ -1,16 +1,36 @@
-// use crate::utils::{errors::Error, file, template};
+use super::router::RouteRegistrar;
+use crate::{
+    config::NebulaConfig,
+    template,
+    utils::{self, errors::Error},
+};
 
-// pub struct HandlerGenerator;
+pub struct HandlerGenerator {
+    name: String,
+}
 
-// impl HandlerGenerator {
-//     pub fn generate(api_dir: &str) -> Result<(), Error> {
-//         let template_path = "src/templates/api/handler.toml";
-//         let template = template::load_template(template_path)?;
-//         let content = template::replace_placeholders(&template, &[]);
+impl HandlerGenerator {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
 
-//         let file_path = format!("{}/handler.rs", api_dir);
-//         file::write_file(&file_path, &content)?;
-//         println!("Handler généré avec succès !");
-//         Ok(())
-//     }
-// }
+    pub fn generate(&self) -> Result<(), Error> {
+        let config = NebulaConfig::load()?;
+        if !config.project.server_type.has_rest() {
+            return Err(Error::ConfigError(
+                "REST is not enabled, set [project] server_type to \"rest\" or \"both\"".into(),
+            ));
+        }
+        let entity_dir = utils::tools::get_entity_dir(&config, &self.name)?;
+        let content = template::get_handler_template(&self.name)?;
+        utils::file::write(&entity_dir.join("handler.rs"), &content)?;
+        utils::file::add_mod_declaration(&entity_dir.join("mod.rs"), "pub mod handler;")?;
+        if !utils::file::exists(&entity_dir.join("routes.rs")) {
+            let registrar = RouteRegistrar::new(&config)?;
+            registrar.register_entity(&self.name, &registrar.prefix_of(&self.name)?)?;
+        }
+
+        println!("✅ Generated handler for {}", self.name);
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+use super::field::Relation;
+use super::migration::MigrationGenerator;
+use super::relations;
+use super::resolver::ResolverGenerator;
+use super::router::RouteRegistrar;
+use crate::{
+    config::NebulaConfig,
+    template::{self, JoinContext},
+    utils::{self, errors::Error},
+};
+use std::path::Path;
+
+/// A `many_to_many` relation of an entity: the join table, the accessors of
+/// both entities and the endpoints attaching and detaching related items.
+pub struct ManyToManyGenerator {
+    entity: String,
+    field: String,
+    target: String,
+    through: Option<String>,
+}
+
+impl ManyToManyGenerator {
+    pub fn new(entity: String, field: String, target: String, through: Option<String>) -> Self {
+        Self {
+            entity,
+            field,
+            target,
+            through,
+        }
+    }
+
+    /// Writes the relation, once `validate` passed.
+    pub fn generate(&self, config: &NebulaConfig) -> Result<(), Error> {
+        let src = config.api_src_path()?;
+        let entity_module = self.entity.to_lowercase();
+        let target_module = self.target.to_lowercase();
+        let source_column = format!("{}_id", entity_module);
+        let target_column = format!("{}_id", target_module);
+        let entity_table = utils::tools::pluralize(&entity_module);
+        let target_table = utils::tools::pluralize(&target_module);
+
+        let join_table = match &self.through {
+            Some(through) => {
+                self.check_through(config)?;
+                // Attaching twice must not link the same entities twice
+                let join_table = utils::tools::pluralize(&through.to_lowercase());
+                let mut columns = [source_column.as_str(), target_column.as_str()];
+                columns.sort();
+                MigrationGenerator::generate_for_unique_index(config, &join_table, &columns)?;
+                join_table
+            }
+            None => {
+                let join_table = join_table(&self.entity, &self.target);
+                let database = config.project.database;
+                let columns = [
+                    (&source_column, &entity_table),
+                    (&target_column, &target_table),
+                ]
+                .iter()
+                .map(|(column, table)| {
+                    format!(
+                        "{} {} NOT NULL REFERENCES {}(id) ON DELETE CASCADE",
+                        column,
+                        database.sql_type("i32"),
+                        table
+                    )
+                })
+                .chain([format!(
+                    "PRIMARY KEY ({}, {})",
+                    source_column, target_column
+                )])
+                .collect::<Vec<_>>();
+                MigrationGenerator::generate_for_join_table(config, &join_table, &columns)?;
+                join_table
+            }
+        };
+
+        let relation = JoinContext {
+            name: self.entity.clone(),
+            target: self.target.clone(),
+            field: self.field.clone(),
+            join_table: join_table.clone(),
+            target_table,
+            source_column: source_column.clone(),
+            target_column: target_column.clone(),
+            through: self.through.is_some(),
+            endpoints: true,
+        };
+        let entity_dir = src.join(&entity_module);
+        self.write_side(
+            &relation,
+            &entity_dir,
+            &format!("pub mod {};", self.field),
+            config,
+        )?;
+
+        // The inverse side only gets the accessors, unless it declares the
+        // relation itself
+        let mut inverse = JoinContext {
+            name: self.target.clone(),
+            target: self.entity.clone(),
+            field: entity_table.clone(),
+            join_table,
+            target_table: entity_table,
+            source_column: target_column,
+            target_column: source_column,
+            through: self.through.is_some(),
+            endpoints: false,
+        };
+        let target_dir = src.join(&target_module);
+        if let Some(field) = self.inverse_field(config, &target_dir, &inverse)? {
+            inverse.field = field;
+            self.write_side(
+                &inverse,
+                &target_dir,
+                &format!("mod {};", inverse.field),
+                config,
+            )?;
+        }
+
+        let server_type = config.project.server_type;
+        if server_type.has_rest() {
+            let registrar = RouteRegistrar::new(config)?;
+            registrar.register_relation(
+                &self.entity,
+                &self.field,
+                &self.target,
+                &registrar.prefix_of(&self.entity)?,
+            )?;
+        }
+        if server_type.has_graphql() {
+            ResolverGenerator::update_schema(&src)?;
+            RouteRegistrar::new(config)?.register_graphql()?;
+        }
+
+        println!(
+            "✅ Generated many_to_many relation {}.{} <-> {}.{}",
+            self.entity, self.field, self.target, inverse.field
+        );
+        Ok(())
+    }
+
+    /// Accessor of the inverse side: the table of the entity, or the field
+    /// followed by the table when the target already has a relation of that
+    /// name. `None` when the target declares the relation itself or already
+    /// has these accessors.
+    fn inverse_field(
+        &self,
+        config: &NebulaConfig,
+        dir: &Path,
+        inverse: &JoinContext,
+    ) -> Result<Option<String>, Error> {
+        let project = relations::scan(config)?;
+        let taken = project
+            .iter()
+            .find(|e| e.name == self.target)
+            .map(|e| e.relations.as_slice())
+            .unwrap_or_default();
+        let declared = taken.iter().any(|f| {
+            matches!(&f.relation, Some(Relation::ManyToMany(target, _)) if *target == self.entity)
+        });
+        if declared {
+            return Ok(None);
+        }
+
+        let candidates = inverse_fields(&self.field, &inverse.target_table);
+        for field in &candidates {
+            let path = dir.join(format!("{}.rs", field));
+            let accessors = JoinContext {
+                field: field.clone(),
+                ..inverse.clone()
+            };
+            let rendered = template::get_many_to_many_template(
+                &accessors,
+                config.project.database,
+                config.project.server_type,
+            )?;
+            if utils::file::read_to_string(&path).ok() == Some(rendered) {
+                return Ok(None);
+            }
+            if !taken.iter().any(|f| f.name == *field)
+                && !utils::file::exists(&path)
+                && !utils::file::exists(&dir.join(field))
+            {
+                return Ok(Some(field.clone()));
+            }
+        }
+        Err(Error::InvalidRelationType(format!(
+            "{}: {} already has relations named {}, rename the field",
+            self.field,
+            self.target,
+            candidates.join(" and ")
+        )))
+    }
+
+    fn write_side(
+        &self,
+        relation: &JoinContext,
+        dir: &Path,
+        declaration: &str,
+        config: &NebulaConfig,
+    ) -> Result<(), Error> {
+        let render = |relation: &JoinContext| {
+            template::get_many_to_many_template(
+                relation,
+                config.project.database,
+                config.project.server_type,
+            )
+        };
+        let path = dir.join(format!("{}.rs", relation.field));
+        let mod_path = dir.join("mod.rs");
+        let private = format!("mod {};", relation.field);
+
+        // An entity declaring a relation it only had the accessors of, as
+        // the other side, takes over the private module
+        let accessors = JoinContext {
+            endpoints: false,
+            ..relation.clone()
+        };
+        let upgraded = relation.endpoints
+            && utils::file::read_to_string(&mod_path)
+                .is_ok_and(|content| content.lines().any(|l| l.trim() == private));
+        if upgraded {
+            utils::file::remove_mod_declaration(&mod_path, &private)?;
+        }
+
+        let content = render(relation)?;
+        if upgraded && utils::file::read_to_string(&path).ok() == Some(render(&accessors)?) {
+            utils::file::update(&path, &content)?;
+        } else {
+            utils::file::write(&path, &content)?;
+        }
+        utils::file::add_mod_declaration(&mod_path, declaration)
+    }
+
+    /// Checks that the `through` entity belongs to both entities, on the
+    /// columns the accessors join. It must be generated before the relation.
+    pub fn check_through(&self, config: &NebulaConfig) -> Result<(), Error> {
+        let Some(through) = &self.through else {
+            return Ok(());
+        };
+        let sides = [&self.entity, &self.target];
+        let specs = sides.map(|e| format!("{}:belongs_to:{}", e.to_lowercase(), e));
+        let project = relations::scan(config)?;
+        let Some(join) = project.iter().find(|e| e.name == *through) else {
+            return Err(Error::InvalidRelationType(format!(
+                "{}: {} does not exist, generate it first with -r \"{}\"",
+                self.field,
+                through,
+                specs.join(",")
+            )));
+        };
+
+        let missing = sides
+            .iter()
+            .zip(&specs)
+            .filter(|(side, _)| {
+                !join.relations.iter().any(|f| {
+                    f.name == side.to_lowercase()
+                        && matches!(&f.relation, Some(Relation::BelongsTo(t)) if t == **side)
+                })
+            })
+            .map(|(_, spec)| spec.as_str())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(Error::InvalidRelationType(format!(
+                "{}: {} needs {} to join {} and {}",
+                self.field,
+                through,
+                missing.join(", "),
+                self.entity,
+                self.target
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self, config: &NebulaConfig) -> Result<(), Error> {
+        if !config.project.database.is_sql() {
+            return Err(Error::InvalidRelationType(format!(
+                "{}: many_to_many relations need a join table, {} has none",
+                self.field,
+                config.project.database.as_str()
+            )));
+        }
+        if self.target == self.entity {
+            return Err(Error::InvalidRelationType(format!(
+                "{}: {} cannot have a many_to_many relation to itself",
+                self.field, self.entity
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Names the accessors of the inverse side of the relation `field` may take,
+/// in order, where `table` is the table of the declaring entity.
+pub fn inverse_fields(field: &str, table: &str) -> [String; 2] {
+    [table.to_string(), format!("{}_{}", field, table)]
+}
+
+/// The table joining `entity` and `target`, named the same way from both
+/// sides.
+pub fn join_table(entity: &str, target: &str) -> String {
+    let mut tables = [entity, target].map(|name| utils::tools::pluralize(&name.to_lowercase()));
+    tables.sort();
+    tables.join("_")
+}
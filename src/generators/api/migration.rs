@@ -0,0 +1,284 @@
+use super::field::EnumType;
+use crate::{
+    config::NebulaConfig,
+    template,
+    types::DatabaseType,
+    utils::{self, errors::Error},
+};
+use std::path::{Path, PathBuf};
+
+pub struct MigrationGenerator {
+    name: String,
+}
+
+impl MigrationGenerator {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    pub fn generate(&self) -> Result<(), Error> {
+        self.validate()?;
+        let config = NebulaConfig::load()?;
+        let database = config.project.database;
+        if !database.is_sql() {
+            return Err(Error::Migration(format!(
+                "{} projects do not use SQL migrations",
+                database.as_str()
+            )));
+        }
+
+        let dir = config.migrations_path();
+        if self.exists_in(&dir)? {
+            return Err(Error::ElementAlreadyExists(format!(
+                "migration {}",
+                self.name
+            )));
+        }
+
+        let (up, down) = match self.name.strip_prefix("create_") {
+            Some(table) => Self::create_table(table, &[], &[], database)?,
+            None => (
+                template::get_empty_migration_template(&self.name, "up")?,
+                template::get_empty_migration_template(&self.name, "down")?,
+            ),
+        };
+        self.write(&dir, &up, &down)
+    }
+
+    /// Writes the `create_{table}` migration for an entity, unless one was
+    /// already generated for it. `enums` are the inline enums of its columns.
+    pub fn generate_for_table(
+        config: &NebulaConfig,
+        table: &str,
+        columns: &[String],
+        enums: &[EnumType],
+    ) -> Result<(), Error> {
+        let database = config.project.database;
+        Self::new(format!("create_{}", table)).generate_once(config, || {
+            Self::create_table(table, columns, enums, database)
+        })
+    }
+
+    /// Writes the `create_{table}` migration of a many-to-many join table,
+    /// whose columns are given as is.
+    pub fn generate_for_join_table(
+        config: &NebulaConfig,
+        table: &str,
+        columns: &[String],
+    ) -> Result<(), Error> {
+        Self::new(format!("create_{}", table)).generate_once(config, || {
+            Ok((
+                template::get_create_table_migration_template(table, columns, &[])?,
+                template::get_drop_table_migration_template(table, &[])?,
+            ))
+        })
+    }
+
+    /// Writes the `drop_{table}` migration of a removed entity. It undoes the
+    /// `create_{table}` migration and the columns added to the table since,
+    /// which it restores when reverted.
+    pub fn generate_for_drop_table(config: &NebulaConfig, table: &str) -> Result<(), Error> {
+        let dir = config.migrations_path();
+        Self::new(format!("drop_{}", table)).generate_once(config, || {
+            let Some(create) = Self::latest(&dir, &format!("create_{}", table))? else {
+                return Ok((
+                    template::get_drop_table_migration_template(table, &[])?,
+                    template::get_empty_migration_template(&format!("drop_{}", table), "down")?,
+                ));
+            };
+            let up = utils::file::read_to_string(&create.with_extension("down.sql"))?;
+            let mut down = utils::file::read_to_string(&create.with_extension("up.sql"))?;
+
+            let created = Self::version(&create);
+            let suffix = format!("_to_{}.up.sql", table);
+            for path in utils::file::list_dir(&dir)? {
+                let added = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.split_once('_'))
+                    .is_some_and(|(_, name)| name.starts_with("add_") && name.ends_with(&suffix));
+                if added && Self::version(&path) > created {
+                    down.push('\n');
+                    down.push_str(&utils::file::read_to_string(&path)?);
+                }
+            }
+            Ok((up, down))
+        })
+    }
+
+    /// Writes the `add_{column}_to_{table}` migration adding `column`, given
+    /// as its SQL definition, to an existing table.
+    pub fn generate_for_column(
+        config: &NebulaConfig,
+        table: &str,
+        column: &str,
+    ) -> Result<(), Error> {
+        let name = column.split_whitespace().next().unwrap_or(column);
+        Self::new(format!("add_{}_to_{}", name, table)).generate_once(config, || {
+            Ok((
+                template::get_add_column_migration_template(table, column)?,
+                template::get_drop_column_migration_template(table, name)?,
+            ))
+        })
+    }
+
+    /// Writes the `add_unique_{columns}_to_{table}` migration of a unique
+    /// index on `columns` of an existing table.
+    pub fn generate_for_unique_index(
+        config: &NebulaConfig,
+        table: &str,
+        columns: &[&str],
+    ) -> Result<(), Error> {
+        let database = config.project.database;
+        let name = columns.join("_");
+        let index = format!("{}_{}_key", table, name);
+        Self::new(format!("add_unique_{}_to_{}", name, table)).generate_once(config, || {
+            Ok((
+                template::get_add_unique_index_migration_template(table, &index, columns)?,
+                template::get_drop_index_migration_template(table, &index, database)?,
+            ))
+        })
+    }
+
+    /// Writes the `create_{type}_enum` migration of a PostgreSQL enum type.
+    /// The other databases declare the values on the column.
+    pub fn generate_for_enum(config: &NebulaConfig, enum_type: &EnumType) -> Result<(), Error> {
+        if config.project.database != DatabaseType::Postgresql {
+            return Ok(());
+        }
+
+        let context = enum_type.to_context();
+        Self::new(format!("create_{}_enum", enum_type.type_name())).generate_once(config, || {
+            Ok((
+                template::get_create_enum_migration_template(&context)?,
+                template::get_drop_enum_migration_template(&context)?,
+            ))
+        })
+    }
+
+    /// Writes the migration built by `migration` in SQL projects, unless one
+    /// with the same name was already generated and not undone since.
+    fn generate_once(
+        &self,
+        config: &NebulaConfig,
+        migration: impl FnOnce() -> Result<(String, String), Error>,
+    ) -> Result<(), Error> {
+        if !config.project.database.is_sql() {
+            return Ok(());
+        }
+
+        let dir = config.migrations_path();
+        if self.exists_in(&dir)? {
+            println!("⚠️  Migration {} already exists, skipping", self.name);
+            return Ok(());
+        }
+
+        let (up, down) = migration()?;
+        self.write(&dir, &up, &down)
+    }
+
+    fn create_table(
+        table: &str,
+        columns: &[String],
+        enums: &[EnumType],
+        database: DatabaseType,
+    ) -> Result<(String, String), Error> {
+        let enums = enums
+            .iter()
+            .filter(|e| database == DatabaseType::Postgresql && !e.shared)
+            .map(EnumType::to_context)
+            .collect::<Vec<_>>();
+        let columns = std::iter::once(database.primary_key().to_string())
+            .chain(columns.iter().cloned())
+            .chain([
+                database.timestamp_column("created_at"),
+                database.timestamp_column("updated_at"),
+            ])
+            .collect::<Vec<_>>();
+
+        Ok((
+            template::get_create_table_migration_template(table, &columns, &enums)?,
+            template::get_drop_table_migration_template(table, &enums)?,
+        ))
+    }
+
+    /// The current timestamp, or the version after the latest migration when
+    /// several are generated within the same second: sqlx needs unique
+    /// versions.
+    fn next_version(dir: &Path) -> Result<u64, Error> {
+        let now = chrono::Local::now()
+            .format("%Y%m%d%H%M%S")
+            .to_string()
+            .parse::<u64>()
+            .unwrap_or_default();
+        let latest = utils::file::list_dir(dir)?
+            .iter()
+            .filter_map(|path| Self::version(path))
+            .max()
+            .unwrap_or_default();
+        Ok(now.max(latest + 1))
+    }
+
+    /// Whether the migration was generated, and not undone by a later one:
+    /// a dropped table can be created again, and dropped again after that.
+    fn exists_in(&self, dir: &Path) -> Result<bool, Error> {
+        let Some(latest) = Self::latest(dir, &self.name)? else {
+            return Ok(false);
+        };
+        let opposite = match (
+            self.name.strip_prefix("create_"),
+            self.name.strip_prefix("drop_"),
+        ) {
+            (Some(table), _) => format!("drop_{}", table),
+            (_, Some(table)) => format!("create_{}", table),
+            _ => return Ok(true),
+        };
+        let undone = Self::latest(dir, &opposite)?
+            .and_then(|path| Self::version(&path))
+            .is_some_and(|version| Some(version) > Self::version(&latest));
+        Ok(!undone)
+    }
+
+    /// The path, without extension, of the latest migration named `name`.
+    fn latest(dir: &Path, name: &str) -> Result<Option<PathBuf>, Error> {
+        let suffix = format!("_{}.up.sql", name);
+        Ok(utils::file::list_dir(dir)?
+            .into_iter()
+            .filter(|path| path.to_string_lossy().ends_with(&suffix))
+            .filter_map(|path| {
+                let base = path.to_string_lossy().strip_suffix(".up.sql")?.to_string();
+                Some(PathBuf::from(base))
+            })
+            .max_by_key(|path| Self::version(path)))
+    }
+
+    fn version(path: &Path) -> Option<u64> {
+        let name = path.file_name()?.to_str()?;
+        name.split('_').next()?.parse().ok()
+    }
+
+    fn write(&self, dir: &Path, up: &str, down: &str) -> Result<(), Error> {
+        let base: PathBuf = dir.join(format!("{}_{}", Self::next_version(dir)?, self.name));
+
+        utils::file::write(&base.with_extension("up.sql"), up)?;
+        utils::file::write(&base.with_extension("down.sql"), down)?;
+
+        println!("✅ Generated migration {}", base.display());
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let valid = !self.name.is_empty()
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid {
+            return Err(Error::ValidationError(format!(
+                "Invalid migration name '{}', expected snake_case",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
@@ -1,21 +1,45 @@
 pub mod entity;
+pub mod enums;
+pub mod field;
 pub mod handler;
+pub mod many_to_many;
+pub mod migration;
+pub mod relations;
+pub mod remove;
+pub mod resolver;
+pub mod router;
+pub mod routes;
+pub mod schema;
 
-use std::fs;
 use std::path::Path;
 use std::process::Command;
 
 use crate::template;
-use crate::utils::errors::Error;
+use crate::types::{DatabaseType, ServerType};
+use crate::utils::{changes, errors::Error, file};
 
 pub struct ApiGenerator {
     api_dir: String,
+    database: DatabaseType,
+    server_type: ServerType,
+    migrations_dir: String,
+    workspace: bool,
 }
 
 impl ApiGenerator {
-    pub fn new(api_dir: &str) -> Self {
+    pub fn new(
+        api_dir: &str,
+        database: DatabaseType,
+        server_type: ServerType,
+        migrations_dir: &str,
+        workspace: bool,
+    ) -> Self {
         Self {
             api_dir: api_dir.to_owned(),
+            database,
+            server_type,
+            migrations_dir: migrations_dir.to_owned(),
+            workspace,
         }
     }
 
@@ -24,19 +48,38 @@ impl ApiGenerator {
         self.init_cargo()?;
         self.init_main()?;
         self.init_server()?;
+        self.init_middleware()?;
         self.init_route()?;
+        self.init_database()?;
+        self.init_schema()?;
 
         Ok(())
     }
 
     fn init_cargo_project(&self) -> Result<(), Error> {
-        Command::new("cargo")
-            .arg("init")
-            .arg(&self.api_dir)
+        // Every file cargo would create is rewritten by the steps below, and
+        // cargo refuses to init an existing package
+        let manifest = Path::new(&self.api_dir).join("Cargo.toml");
+        if changes::is_dry_run() || manifest.exists() {
+            return Ok(());
+        }
+
+        let mut command = Command::new("cargo");
+        command.arg("init").arg(&self.api_dir);
+        // Workspace members share the repository of the workspace root
+        if self.workspace {
+            command.args(["--vcs", "none"]);
+        }
+
+        command
             .status()
-            .map_err(|e| Error::FileSystem(e))
+            .map_err(Error::FileSystem)
             .and_then(|status| {
                 if status.success() {
+                    // The files cargo just created are replaced by ours
+                    for file in ["Cargo.toml", "src/main.rs", ".gitignore"] {
+                        changes::own(&Path::new(&self.api_dir).join(file));
+                    }
                     Ok(())
                 } else {
                     Err(Error::Prompt(
@@ -53,9 +96,14 @@ impl ApiGenerator {
             .and_then(|name| name.to_str())
             .ok_or_else(|| Error::InvalidPath)?;
 
-        let content = template::get_cargo_template(project_name);
+        let content = template::get_cargo_template(
+            project_name,
+            self.database,
+            self.server_type,
+            self.workspace,
+        )?;
         let cargo_path = project_path.join("Cargo.toml");
-        fs::write(&cargo_path, content).map_err(Error::FileSystem)?;
+        file::write(&cargo_path, &content)?;
 
         println!("✅ Generated Cargo.toml file");
         Ok(())
@@ -64,11 +112,11 @@ impl ApiGenerator {
     fn init_main(&self) -> Result<(), Error> {
         let project_dir = Path::new(&self.api_dir);
         // Generate content from template
-        let content = template::get_main_template();
+        let content = template::get_main_template(self.server_type)?;
 
         // Create src/main.rs file
         let main_path = project_dir.join("src/main.rs");
-        fs::write(&main_path, content).map_err(Error::FileSystem)?;
+        file::write(&main_path, &content)?;
 
         println!("✅ Generated main.rs file");
         Ok(())
@@ -77,26 +125,69 @@ impl ApiGenerator {
     fn init_server(&self) -> Result<(), Error> {
         let project_dir = Path::new(&self.api_dir);
         // Generate content from template
-        let content = template::get_server_template();
+        let content = template::get_server_template()?;
 
         // Create src/server.rs file
         let server_path = project_dir.join("src/server.rs");
-        fs::write(&server_path, content).map_err(Error::FileSystem)?;
+        file::write(&server_path, &content)?;
 
         println!("✅ Generated server.rs file");
         Ok(())
     }
 
+    fn init_middleware(&self) -> Result<(), Error> {
+        let project_dir = Path::new(&self.api_dir);
+        // Generate content from template
+        let content = template::get_middleware_template()?;
+
+        // Create src/middleware.rs file
+        let middleware_path = project_dir.join("src/middleware.rs");
+        file::write(&middleware_path, &content)?;
+
+        println!("✅ Generated middleware.rs file");
+        Ok(())
+    }
+
     fn init_route(&self) -> Result<(), Error> {
         let project_dir = Path::new(&self.api_dir);
         // Generate content from template
-        let content = template::get_route_template();
+        let content = template::get_route_template(self.server_type, self.workspace)?;
 
         // Create src/route.rs file
         let route_path = project_dir.join("src/route.rs");
-        fs::write(&route_path, content).map_err(Error::FileSystem)?;
+        file::write(&route_path, &content)?;
 
         println!("✅ Generated route.rs file");
         Ok(())
     }
+
+    fn init_database(&self) -> Result<(), Error> {
+        let project_dir = Path::new(&self.api_dir);
+        // Generate content from template
+        let content = template::get_database_template(self.database, &self.migrations_dir)?;
+
+        // Create src/database.rs file
+        let database_path = project_dir.join("src/database.rs");
+        file::write(&database_path, &content)?;
+
+        println!("✅ Generated database.rs file");
+        Ok(())
+    }
+
+    fn init_schema(&self) -> Result<(), Error> {
+        if !self.server_type.has_graphql() {
+            return Ok(());
+        }
+
+        let project_dir = Path::new(&self.api_dir);
+        // Generate content from template
+        let content = template::get_schema_template(&[], &[])?;
+
+        // Create src/schema.rs file
+        let schema_path = project_dir.join("src/schema.rs");
+        file::write(&schema_path, &content)?;
+
+        println!("✅ Generated schema.rs file");
+        Ok(())
+    }
 }
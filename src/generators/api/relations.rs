@@ -0,0 +1,510 @@
+//! Consistency of the relations between the entities of a project. Every
+//! entity file lists its relations on a `// Relations:` line, in the syntax of
+//! `-r`, which is how the relations of the other entities are read back. The
+//! loaders of an entity's relations, in its `relations.rs`, are generated from
+//! that line.
+
+use super::field::{split_fields, EntityField, Relation};
+use super::migration::MigrationGenerator;
+use crate::{
+    config::NebulaConfig,
+    template,
+    types::DatabaseType,
+    utils::{self, errors::Error, source},
+};
+use std::path::Path;
+
+const MARKER: &str = "// Relations: ";
+const COMPLEX_ATTRIBUTE: &str = "#[graphql(complex)]";
+
+/// An entity of the project and its relations.
+pub struct ProjectEntity {
+    pub name: String,
+    pub relations: Vec<EntityField>,
+}
+
+/// The other side of a relation, missing from the project.
+#[derive(Debug, Clone)]
+pub enum MissingSide {
+    /// The target entity does not exist, it can be generated with the inverse
+    /// relations. `first` when the entity's table references it.
+    Entity {
+        name: String,
+        inverses: Vec<String>,
+        first: bool,
+    },
+    /// The target entity exists without a relation back.
+    Inverse { entity: String, inverse: String },
+}
+
+/// Entities of the project, read from their `entity.rs`.
+pub fn scan(config: &NebulaConfig) -> Result<Vec<ProjectEntity>, Error> {
+    let mut entities = Vec::new();
+    for dir in utils::file::list_dir(&config.api_src_path()?)? {
+        let path = dir.join("entity.rs");
+        if !utils::file::exists(&path) {
+            continue;
+        }
+        let module = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or(Error::InvalidPath)?;
+        let content = utils::file::read_to_string(&path)?;
+        let Some(name) = struct_name(&content, module) else {
+            continue;
+        };
+        let relations = content
+            .lines()
+            .find_map(|l| l.strip_prefix(MARKER))
+            .map(split_fields)
+            .unwrap_or_default()
+            .iter()
+            .map(|spec| EntityField::new(spec, &name, &[]))
+            .collect::<Result<Vec<_>, _>>()?;
+        entities.push(ProjectEntity { name, relations });
+    }
+    Ok(entities)
+}
+
+/// Checks the relations of `entity` against the other entities of the
+/// project. Conflicting cardinalities fail, missing sides are returned.
+pub fn check(
+    entity: &str,
+    relations: &[&EntityField],
+    project: &[ProjectEntity],
+) -> Result<Vec<MissingSide>, Error> {
+    let mut missing: Vec<MissingSide> = Vec::new();
+
+    for field in relations {
+        let Some(relation) = &field.relation else {
+            continue;
+        };
+        let target = relation.target();
+        if target == entity {
+            continue;
+        }
+
+        let inverse = inverse_spec(entity, relation);
+        let Some(other) = project.iter().find(|e| e.name == target) else {
+            let first = !matches!(relation, Relation::HasOne(_) | Relation::HasMany(_));
+            match missing
+                .iter_mut()
+                .find(|side| matches!(side, MissingSide::Entity { name, .. } if name == target))
+            {
+                Some(MissingSide::Entity {
+                    inverses,
+                    first: target_first,
+                    ..
+                }) => {
+                    inverses.extend(inverse.filter(|i| !inverses.contains(i)));
+                    *target_first |= first;
+                }
+                _ => missing.push(MissingSide::Entity {
+                    name: target.to_string(),
+                    inverses: inverse.into_iter().collect(),
+                    first,
+                }),
+            }
+            continue;
+        };
+
+        let back = other
+            .relations
+            .iter()
+            .filter_map(|f| Some((f, f.relation.as_ref()?)))
+            .filter(|(_, r)| r.target() == entity)
+            .collect::<Vec<_>>();
+        if back.iter().any(|(_, r)| compatible(relation, r)) {
+            continue;
+        }
+        if let Some((other_field, other_relation)) = back.first() {
+            return Err(Error::InvalidRelationType(format!(
+                "{}.{} is {} but {}.{} is {}, {}",
+                entity,
+                field.name,
+                relation.kind(),
+                target,
+                other_field.name,
+                other_relation.kind(),
+                conflict_hint(relation, other_relation)
+            )));
+        }
+        // The inverse of a many-to-many relation is generated with it
+        if let Some(mut inverse) = inverse {
+            // Rows already in the table have no value for a new foreign key
+            if matches!(relation, Relation::HasOne(_) | Relation::HasMany(_)) {
+                inverse.push('?');
+            }
+            missing.push(MissingSide::Inverse {
+                entity: target.to_string(),
+                inverse,
+            });
+        }
+    }
+    Ok(missing)
+}
+
+/// Adds `inverse` to the existing entity `name`, with the column of the
+/// foreign key for a `belongs_to`. Its loaders are written by `write_loaders`.
+pub fn add_inverse(config: &NebulaConfig, name: &str, inverse: &str) -> Result<(), Error> {
+    let field = EntityField::new(inverse, name, &[])?;
+    let database = config.project.database;
+    let dir = config.api_src_path()?.join(name.to_lowercase());
+    // The accessors of the inverse side of a many-to-many relation are a
+    // module of the same name
+    if utils::file::exists(&dir.join(format!("{}.rs", field.name))) {
+        return Err(Error::InvalidRelationType(format!(
+            "{}.{} is already a many_to_many relation, declare the relation back with another name",
+            name, field.name
+        )));
+    }
+    let path = dir.join("entity.rs");
+    let not_found = || {
+        Error::ParseError(format!(
+            "could not find `pub struct {}` in {}",
+            name,
+            path.display()
+        ))
+    };
+
+    let content = utils::file::read_to_string(&path)?;
+    let mut updated = add_marker(&content, name, inverse).ok_or_else(not_found)?;
+    if config.project.server_type.has_graphql() {
+        updated = add_complex_attribute(&updated, name).ok_or_else(not_found)?;
+    }
+    if let Some(column) = field.to_context() {
+        updated = add_foreign_key(&updated, name, &column)?.ok_or_else(not_found)?;
+        if database.is_sql() {
+            let inputs =
+                source::struct_fields(&content, &format!("{}Input", name)).ok_or_else(not_found)?;
+            let fields = source::struct_fields(&content, name).ok_or_else(not_found)?;
+            let columns = inputs
+                .into_iter()
+                .map(|(input, _)| {
+                    let json = fields.iter().find(|(field, _)| *field == input).and_then(
+                        |(_, attributes)| attributes.iter().find(|a| a.starts_with("#[sqlx(json")),
+                    );
+                    template::ColumnContext {
+                        name: input,
+                        json: json.is_some(),
+                        optional: json.is_some_and(|a| a.contains("nullable")),
+                    }
+                })
+                .collect::<Vec<_>>();
+            update_repository(
+                &dir.join("repository.rs"),
+                name,
+                &columns,
+                &column,
+                database,
+            )?;
+        }
+    }
+    utils::file::update(&path, &updated)?;
+
+    if let Some(column) = field.to_sql_column(database) {
+        let table = utils::tools::pluralize(&name.to_lowercase());
+        MigrationGenerator::generate_for_column(config, &table, &column)?;
+    }
+
+    println!("✅ Added relation {} to {}", inverse, name);
+    Ok(())
+}
+
+/// Removes the `has_one` and `has_many` relations to `name` from the other
+/// entities, before `name` is removed, and returns the entities changed. An
+/// entity with a foreign key or a join table to `name` must be removed first.
+pub fn remove_inverses(config: &NebulaConfig, name: &str) -> Result<Vec<String>, Error> {
+    let project = scan(config)?;
+    let points_to_name =
+        |field: &EntityField| field.relation.as_ref().is_some_and(|r| r.target() == name);
+    let dependents = project
+        .iter()
+        .filter(|e| e.name != name && e.relations.iter().any(points_to_name))
+        .collect::<Vec<_>>();
+    for entity in &dependents {
+        let held =
+            entity.relations.iter().find_map(|f| match &f.relation {
+                Some(
+                    relation @ (Relation::BelongsTo(target) | Relation::ManyToMany(target, _)),
+                ) if target == name => Some((f, relation)),
+                _ => None,
+            });
+        if let Some((field, relation)) = held {
+            return Err(Error::InvalidRelationType(format!(
+                "{}.{} is a {} relation to {}, destroy {} or remove the relation first",
+                entity.name,
+                field.name,
+                relation.kind(),
+                name,
+                entity.name
+            )));
+        }
+    }
+
+    let src = config.api_src_path()?;
+    for entity in &dependents {
+        let path = src.join(entity.name.to_lowercase()).join("entity.rs");
+        let content = utils::file::read_to_string(&path)?;
+        let mut removed = Vec::new();
+        let updated = content
+            .lines()
+            .filter_map(|line| {
+                let Some(specs) = line.strip_prefix(MARKER) else {
+                    return Some(line.to_string());
+                };
+                let (kept, dropped): (Vec<_>, Vec<_>) =
+                    split_fields(specs).into_iter().partition(|spec| {
+                        !EntityField::new(spec, &entity.name, &[])
+                            .is_ok_and(|field| points_to_name(&field))
+                    });
+                removed = dropped;
+                (!kept.is_empty()).then(|| format!("{}{}", MARKER, kept.join(", ")))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        utils::file::update(&path, &format!("{}\n", updated))?;
+        println!(
+            "✅ Removed relation {} from {}",
+            removed.join(", "),
+            entity.name
+        );
+    }
+    Ok(dependents.iter().map(|e| e.name.clone()).collect())
+}
+
+/// Writes the `relations.rs` of the entity `name`: a loader for each of its
+/// relations and, with GraphQL, their fields. Run again whenever an entity
+/// it points to changes, since the foreign key of a `has_one` or `has_many`
+/// is the `belongs_to` back on the other side. An entity left without
+/// relations loses the file.
+pub fn write_loaders(config: &NebulaConfig, name: &str) -> Result<(), Error> {
+    let project = scan(config)?;
+    let Some(entity) = project.iter().find(|e| e.name == name) else {
+        return Ok(());
+    };
+    let graphql = config.project.server_type.has_graphql();
+    let relations = entity
+        .relations
+        .iter()
+        .filter_map(|field| loader_context(name, field, &project))
+        .filter(|relation| graphql || relation.kind != "many_to_many")
+        .collect::<Vec<_>>();
+    let dir = config.api_src_path()?.join(name.to_lowercase());
+    if relations.is_empty() {
+        return remove_loaders(&dir);
+    }
+
+    let content =
+        template::get_relations_template(name, &relations, config.project.database, graphql)?;
+    utils::file::update(&dir.join("relations.rs"), &content)?;
+    utils::file::add_mod_declaration(&dir.join("mod.rs"), "mod relations;")
+}
+
+fn remove_loaders(dir: &Path) -> Result<(), Error> {
+    if !utils::file::exists(&dir.join("relations.rs")) {
+        return Ok(());
+    }
+    utils::file::remove_if_exists(&dir.join("relations.rs"))?;
+    utils::file::remove_mod_declaration(&dir.join("mod.rs"), "mod relations;")?;
+
+    // The fields of `relations.rs` were those of the complex object
+    let path = dir.join("entity.rs");
+    let content = utils::file::read_to_string(&path)?;
+    let updated = source::remove_line(&content, COMPLEX_ATTRIBUTE);
+    if updated != content {
+        utils::file::update(&path, &updated)?;
+    }
+    Ok(())
+}
+
+fn loader_context(
+    entity: &str,
+    field: &EntityField,
+    project: &[ProjectEntity],
+) -> Option<template::RelationContext> {
+    let relation = field.relation.as_ref()?;
+    let target = relation.target();
+    let (foreign_key, loaded_type) = match relation {
+        Relation::BelongsTo(_) if field.optional => (field.column(), format!("Option<{}>", target)),
+        Relation::BelongsTo(_) => (field.column(), target.to_string()),
+        Relation::HasOne(_) | Relation::HasMany(_) => {
+            let back = project
+                .iter()
+                .find(|e| e.name == target)
+                .and_then(|e| {
+                    e.relations.iter().find(
+                        |f| matches!(&f.relation, Some(Relation::BelongsTo(t)) if t == entity),
+                    )
+                })
+                .and_then(EntityField::column)
+                .unwrap_or_else(|| format!("{}_id", entity.to_lowercase()));
+            (Some(back), field.rust_type())
+        }
+        Relation::ManyToMany(..) => (None, field.rust_type()),
+    };
+
+    Some(template::RelationContext {
+        name: field.name.clone(),
+        kind: relation.kind().to_string(),
+        target: target.to_string(),
+        target_table: utils::tools::pluralize(&target.to_lowercase()),
+        loaded_type,
+        foreign_key,
+        optional: field.optional,
+    })
+}
+
+/// Adds the foreign key `column` to the entity `name` and to its inputs.
+fn add_foreign_key(
+    content: &str,
+    name: &str,
+    column: &template::FieldContext,
+) -> Result<Option<String>, Error> {
+    let declaration = template::get_field_template(column)?;
+    let field = &column.name;
+    let value = if column.optional {
+        format!("Some({})", field)
+    } else {
+        field.to_string()
+    };
+    let apply = format!(
+        "        if let Some({0}) = input.{0} {{\n            self.{0} = {1};\n        }}",
+        field, value
+    );
+
+    Ok(source::add_struct_field(content, name, &declaration)
+        .and_then(|c| source::add_struct_field(&c, &format!("{}Input", name), &declaration))
+        .and_then(|c| {
+            source::add_struct_field(
+                &c,
+                &format!("Update{}Input", name),
+                &format!("    pub {}: Option<{}>,", field, column.inner_type),
+            )
+        })
+        .and_then(|c| source::insert_before(&c, "pub fn apply(", "    }", &apply))
+        .and_then(|c| {
+            source::insert_before(
+                &c,
+                &format!("fn from(input: {}Input)", name),
+                "            ..Self::default()",
+                &format!("            {0}: input.{0},", field),
+            )
+        }))
+}
+
+/// Regenerates the repository with the new column, unless it was modified
+/// since it was generated with `columns`.
+fn update_repository(
+    path: &Path,
+    name: &str,
+    columns: &[template::ColumnContext],
+    column: &template::FieldContext,
+    database: DatabaseType,
+) -> Result<(), Error> {
+    let table = utils::tools::pluralize(&name.to_lowercase());
+    let mut columns = columns.to_vec();
+    let generated = template::get_sql_repository_template(name, &table, &columns, database)?;
+    if utils::file::read_to_string(path).ok() != Some(generated) {
+        println!(
+            "⚠️  {} has been modified, add {} to its create and update",
+            path.display(),
+            column.name
+        );
+        return Ok(());
+    }
+
+    columns.push(template::ColumnContext {
+        name: column.name.clone(),
+        json: column.json,
+        optional: column.optional,
+    });
+    let content = template::get_sql_repository_template(name, &table, &columns, database)?;
+    utils::file::update(path, &content)
+}
+
+/// The relation the target of `relation` needs back to `entity`.
+fn inverse_spec(entity: &str, relation: &Relation) -> Option<String> {
+    let module = entity.to_lowercase();
+    match relation {
+        Relation::HasOne(_) | Relation::HasMany(_) => {
+            Some(format!("{}:belongs_to:{}", module, entity))
+        }
+        Relation::BelongsTo(_) => Some(format!(
+            "{}:has_many:{}",
+            utils::tools::pluralize(&module),
+            entity
+        )),
+        Relation::ManyToMany(..) => None,
+    }
+}
+
+fn compatible(relation: &Relation, back: &Relation) -> bool {
+    matches!(
+        (relation, back),
+        (
+            Relation::HasOne(_) | Relation::HasMany(_),
+            Relation::BelongsTo(_)
+        ) | (
+            Relation::BelongsTo(_),
+            Relation::HasOne(_) | Relation::HasMany(_)
+        ) | (Relation::ManyToMany(..), Relation::ManyToMany(..))
+    )
+}
+
+fn conflict_hint(relation: &Relation, back: &Relation) -> &'static str {
+    match (relation, back) {
+        (Relation::ManyToMany(..), _) | (_, Relation::ManyToMany(..)) => {
+            "both sides must be many_to_many"
+        }
+        (Relation::HasMany(_), Relation::HasMany(_)) => "use many_to_many on both sides",
+        (Relation::BelongsTo(_), Relation::BelongsTo(_)) => {
+            "only one side holds the foreign key, the other is has_one or has_many"
+        }
+        _ => "one side must be belongs_to",
+    }
+}
+
+/// The struct of the entity `module`, whose name may differ in case.
+fn struct_name(content: &str, module: &str) -> Option<String> {
+    content
+        .lines()
+        .filter_map(|l| l.strip_prefix("pub struct ")?.strip_suffix(" {"))
+        .find(|name| name.to_lowercase() == module)
+        .map(str::to_string)
+}
+
+/// Lets the GraphQL object of the entity `name` have the fields of
+/// `relations.rs`.
+fn add_complex_attribute(content: &str, name: &str) -> Option<String> {
+    if content.lines().any(|l| l == COMPLEX_ATTRIBUTE) {
+        return Some(content.to_string());
+    }
+    source::insert_before(
+        content,
+        "#[derive(",
+        &format!("pub struct {} {{", name),
+        COMPLEX_ATTRIBUTE,
+    )
+}
+
+/// Adds `spec` to the `// Relations:` line of the entity `name`, writing the
+/// line above its struct if there is none.
+fn add_marker(content: &str, name: &str, spec: &str) -> Option<String> {
+    let mut lines = content.lines().map(str::to_string).collect::<Vec<_>>();
+    if let Some(line) = lines.iter_mut().find(|l| l.starts_with(MARKER)) {
+        line.push_str(&format!(", {}", spec));
+    } else {
+        let header = format!("pub struct {} {{", name);
+        let start = lines.iter().position(|l| *l == header)?;
+        let position = lines[..start]
+            .iter()
+            .rposition(|l| l.starts_with("#[derive"))
+            .unwrap_or(start);
+        lines.insert(position, format!("{}{}", MARKER, spec));
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    Some(result)
+}
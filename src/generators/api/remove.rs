@@ -0,0 +1,84 @@
+use super::field::Relation;
+use super::many_to_many;
+use super::migration::MigrationGenerator;
+use super::relations;
+use super::resolver::ResolverGenerator;
+use super::router::RouteRegistrar;
+use crate::{
+    config::NebulaConfig,
+    utils::{self, errors::Error},
+};
+
+/// Removes an entity and what was generated for it: its module, its routes,
+/// its resolvers and the relations of the other entities to it. Its tables
+/// are dropped by a new migration, the history is kept.
+pub struct EntityRemover {
+    name: String,
+}
+
+impl EntityRemover {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    pub fn remove(&self) -> Result<(), Error> {
+        let config = NebulaConfig::load()?;
+        let src = config.api_src_path()?;
+        let entity_dir = utils::tools::get_entity_dir(&config, &self.name)?;
+        let module = self.name.to_lowercase();
+        let table = utils::tools::pluralize(&module);
+        let project = relations::scan(&config)?;
+        let name = project
+            .iter()
+            .find(|e| e.name.to_lowercase() == module)
+            .map(|e| e.name.clone())
+            .unwrap_or_else(|| self.name.clone());
+        let entity_relations = project
+            .into_iter()
+            .find(|e| e.name == name)
+            .map(|e| e.relations)
+            .unwrap_or_default();
+
+        let dependents = relations::remove_inverses(&config, &name)?;
+
+        // The other side of a many-to-many relation only has the accessors
+        let mut join_tables = Vec::new();
+        for field in &entity_relations {
+            let Some(Relation::ManyToMany(target, through)) = &field.relation else {
+                continue;
+            };
+            let target_dir = src.join(target.to_lowercase());
+            for module in many_to_many::inverse_fields(&field.name, &table) {
+                utils::file::remove_if_exists(&target_dir.join(format!("{}.rs", module)))?;
+                utils::file::remove_mod_declaration(
+                    &target_dir.join("mod.rs"),
+                    &format!("mod {};", module),
+                )?;
+            }
+            // A join entity keeps its table until it is removed itself
+            if through.is_none() {
+                join_tables.push(many_to_many::join_table(&name, target));
+            }
+        }
+
+        utils::file::remove_if_exists(&entity_dir)?;
+        utils::file::remove_mod_declaration(&src.join("main.rs"), &format!("pub mod {};", module))?;
+        println!("✅ Removed {}", entity_dir.display());
+
+        RouteRegistrar::new(&config)?.unregister(&name)?;
+        if config.project.server_type.has_graphql() {
+            ResolverGenerator::update_schema(&src)?;
+        }
+        for dependent in &dependents {
+            relations::write_loaders(&config, dependent)?;
+        }
+
+        for join_table in &join_tables {
+            MigrationGenerator::generate_for_drop_table(&config, join_table)?;
+        }
+        MigrationGenerator::generate_for_drop_table(&config, &table)?;
+
+        println!("✅ Removed entity {}", name);
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use super::router::RouteRegistrar;
+use crate::{
+    config::NebulaConfig,
+    template,
+    utils::{self, errors::Error},
+};
+use std::path::Path;
+
+pub struct ResolverGenerator {
+    name: String,
+}
+
+impl ResolverGenerator {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    pub fn generate(&self) -> Result<(), Error> {
+        let config = NebulaConfig::load()?;
+        if !config.project.server_type.has_graphql() {
+            return Err(Error::ConfigError(
+                "GraphQL is not enabled, set [project] server_type to \"graphql\" or \"both\""
+                    .into(),
+            ));
+        }
+        let entity_dir = utils::tools::get_entity_dir(&config, &self.name)?;
+        let content = template::get_resolver_template(&self.name)?;
+        utils::file::write(&entity_dir.join("resolver.rs"), &content)?;
+        utils::file::add_mod_declaration(&entity_dir.join("mod.rs"), "pub mod resolver;")?;
+
+        Self::update_schema(&config.api_src_path()?)?;
+        RouteRegistrar::new(&config)?.register_graphql()?;
+
+        println!("✅ Generated resolver for {}", self.name);
+        Ok(())
+    }
+
+    /// Rewrites `schema.rs` so that its roots merge every resolver found in
+    /// the project: the `resolver.rs` of entities and the objects of their
+    /// other modules, such as many-to-many relations.
+    pub fn update_schema(src_path: &Path) -> Result<(), Error> {
+        let mut queries = Vec::new();
+        let mut mutations = Vec::new();
+
+        let modules = utils::file::list_dir(src_path)?
+            .into_iter()
+            .filter(|path| utils::file::exists(&path.join("mod.rs")))
+            .collect::<Vec<_>>();
+
+        for module_path in modules {
+            let module = module_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or(Error::InvalidPath)?;
+            for file in utils::file::list_dir(&module_path)? {
+                let Some(submodule) = file
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_suffix(".rs"))
+                    .filter(|name| *name != "mod")
+                else {
+                    continue;
+                };
+                let content = utils::file::read_to_string(&file)?;
+                for object in content.lines().filter_map(|l| {
+                    l.strip_prefix("pub struct ")
+                        .and_then(|rest| rest.strip_suffix(';'))
+                }) {
+                    let path = format!("crate::{}::{}::{}", module, submodule, object);
+                    if object.ends_with("Query") {
+                        queries.push(path);
+                    } else if object.ends_with("Mutation") {
+                        mutations.push(path);
+                    }
+                }
+            }
+        }
+
+        let schema_path = src_path.join("schema.rs");
+        utils::file::update(
+            &schema_path,
+            &template::get_schema_template(&queries, &mutations)?,
+        )?;
+        utils::file::add_mod_declaration(&src_path.join("main.rs"), "mod schema;")
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{
+    config::NebulaConfig,
+    template,
+    utils::{self, errors::Error, source},
+};
+use std::path::PathBuf;
+
+const CONFIGURE_SIGNATURE: &str = "pub fn configure()";
+const DEFAULT_PREFIX: &str = "/api/v1";
+
+pub struct RouteRegistrar {
+    route_path: PathBuf,
+}
+
+impl RouteRegistrar {
+    pub fn new(config: &NebulaConfig) -> Result<Self, Error> {
+        Ok(Self {
+            route_path: config.api_src_path()?.join("route.rs"),
+        })
+    }
+
+    /// The prefix the routes of the entity `name` are registered under, the
+    /// default one if it has none yet.
+    pub fn prefix_of(&self, name: &str) -> Result<String, Error> {
+        let content = self.read()?;
+        let suffix = format!("/{}", utils::tools::pluralize(&name.to_lowercase()));
+        // The entity's own path, not the path of a relation of another one
+        let prefix = content
+            .split('"')
+            .skip(1)
+            .step_by(2)
+            .filter_map(|path| path.strip_suffix(&suffix))
+            .find(|prefix| (prefix.is_empty() || prefix.starts_with('/')) && !prefix.contains('{'))
+            .unwrap_or(DEFAULT_PREFIX);
+        Ok(prefix.to_string())
+    }
+
+    /// Registers the flat CRUD routes of an entity handler.
+    pub fn register_entity(&self, name: &str, prefix: &str) -> Result<(), Error> {
+        let base_path = Self::entity_path(prefix, name);
+        let content = self.read()?;
+        let updated = self.insert(&content, &base_path, &Self::entity_routes(prefix, name)?)?;
+        self.write(&content, &updated, &base_path)
+    }
+
+    /// Nests the router of an entity's `routes.rs`, replacing the flat routes
+    /// registered by the handler generator if they are still untouched. The
+    /// routes of its relations move to `prefix` with it.
+    pub fn register_entity_router(&self, name: &str, prefix: &str) -> Result<(), Error> {
+        let nest_path = Self::entity_path(prefix, name);
+        let old_prefix = self.prefix_of(name)?;
+        let old_path = Self::entity_path(&old_prefix, name);
+        let content = self.read()?;
+        let updated = content
+            .replace(
+                &format!("\n{}", Self::entity_routes(&old_prefix, name)?),
+                "",
+            )
+            .replace(
+                &format!("\"{}/{{id}}/", old_path),
+                &format!("\"{}/{{id}}/", nest_path),
+            );
+        let updated = self.insert(
+            &updated,
+            &nest_path,
+            &template::get_nested_router_template(&nest_path, &name.to_lowercase())?,
+        )?;
+        self.write(&content, &updated, &nest_path)
+    }
+
+    /// Registers the routes listing, attaching and detaching the related
+    /// items of a many-to-many relation, under the prefix of the entity.
+    pub fn register_relation(
+        &self,
+        name: &str,
+        field: &str,
+        target: &str,
+        prefix: &str,
+    ) -> Result<(), Error> {
+        let base_path = format!("{}/{{id}}/{}", Self::entity_path(prefix, name), field);
+        let item_path = format!("{}/{{{}_id}}", base_path, target.to_lowercase());
+        let routes = template::get_relation_routes_template(
+            &base_path,
+            &item_path,
+            &format!("crate::{}::{}", name.to_lowercase(), field),
+        )?;
+        let content = self.read()?;
+        let updated = self.insert(&content, &base_path, &routes)?;
+        self.write(&content, &updated, &base_path)
+    }
+
+    pub fn register_graphql(&self) -> Result<(), Error> {
+        let content = self.read()?;
+        let updated = self.insert(
+            &content,
+            "/graphql",
+            &template::get_graphql_route_template()?,
+        )?;
+        self.write(&content, &updated, "/graphql")
+    }
+
+    /// Removes every route of `configure()` that calls into the module of the
+    /// entity `name`: its handler, its nested router and its relations.
+    pub fn unregister(&self, name: &str) -> Result<(), Error> {
+        let module = format!("crate::{}::", name.to_lowercase());
+        let content = self.read()?;
+        let (open, close) = self.configure_body(&content)?;
+        let body = &content[open + 1..close];
+
+        // Each `.route(...)` or `.nest(...)` of the chain starts at the same
+        // indentation and spans the lines up to the next one
+        let indent = |line: &str| line.len() - line.trim_start().len();
+        let Some(chain) = body
+            .lines()
+            .find(|l| l.trim_start().starts_with('.'))
+            .map(indent)
+        else {
+            return Ok(());
+        };
+        let mut calls: Vec<Vec<&str>> = Vec::new();
+        for line in body.split('\n') {
+            let starts = indent(line) < chain
+                || (indent(line) == chain && line.trim_start().starts_with('.'));
+            match calls.last_mut() {
+                Some(call) if !starts => call.push(line),
+                _ => calls.push(vec![line]),
+            }
+        }
+        let kept = calls
+            .into_iter()
+            .filter(|call| !call.iter().any(|l| l.contains(&module)))
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let updated = format!("{}{}{}", &content[..open + 1], kept, &content[close..]);
+        if updated != content {
+            utils::file::update(&self.route_path, &updated)?;
+            println!("✅ Unregistered {} routes from route.rs", name);
+        }
+        Ok(())
+    }
+
+    fn entity_path(prefix: &str, name: &str) -> String {
+        format!(
+            "{}/{}",
+            prefix.trim_end_matches('/'),
+            utils::tools::pluralize(&name.to_lowercase())
+        )
+    }
+
+    fn entity_routes(prefix: &str, name: &str) -> Result<String, Error> {
+        let module = name.to_lowercase();
+        let routes = template::get_entity_routes_template(
+            &Self::entity_path(prefix, name),
+            &format!("crate::{}::handler", module),
+        )?;
+        Ok(routes.trim_end().to_string())
+    }
+
+    fn read(&self) -> Result<String, Error> {
+        utils::file::read_to_string(&self.route_path).map_err(|_| {
+            Error::ResourceNotFound(format!("route file {}", self.route_path.display()))
+        })
+    }
+
+    fn write(&self, content: &str, updated: &str, path: &str) -> Result<(), Error> {
+        if updated != content {
+            utils::file::update(&self.route_path, updated)?;
+            println!("✅ Registered {} routes in route.rs", path);
+        }
+        Ok(())
+    }
+
+    /// Appends `routes` to the Router expression returned by `configure()`,
+    /// unless `path` is already routed.
+    fn insert(&self, content: &str, path: &str, routes: &str) -> Result<String, Error> {
+        if content.contains(&format!("\"{}\"", path)) {
+            return Ok(content.to_string());
+        }
+
+        let (open, close) = self.configure_body(content)?;
+        let body = content[open + 1..close].trim_end();
+        if body.ends_with(';') || body.trim().is_empty() {
+            return Err(Error::ParseError(format!(
+                "`configure()` in {} must end with a Router expression",
+                self.route_path.display()
+            )));
+        }
+
+        let mut updated = content.to_string();
+        updated.insert_str(open + 1 + body.len(), &format!("\n{}", routes.trim_end()));
+        Ok(updated)
+    }
+
+    fn configure_body(&self, content: &str) -> Result<(usize, usize), Error> {
+        source::find_fn_body(content, CONFIGURE_SIGNATURE).ok_or_else(|| {
+            Error::ParseError(format!(
+                "could not find `{}` in {}",
+                CONFIGURE_SIGNATURE,
+                self.route_path.display()
+            ))
+        })
+    }
+}
@@ -0,0 +1,54 @@
+use super::router::RouteRegistrar;
+use crate::{
+    config::NebulaConfig,
+    template,
+    utils::{self, errors::Error},
+};
+
+pub struct RoutesGenerator {
+    name: String,
+    prefix: String,
+}
+
+impl RoutesGenerator {
+    pub fn new(name: String, prefix: String) -> Self {
+        Self { name, prefix }
+    }
+
+    pub fn generate(&self) -> Result<(), Error> {
+        self.validate()?;
+        let config = NebulaConfig::load()?;
+        if !config.project.server_type.has_rest() {
+            return Err(Error::ConfigError(
+                "REST is not enabled, set [project] server_type to \"rest\" or \"both\"".into(),
+            ));
+        }
+        let entity_dir = utils::tools::get_entity_dir(&config, &self.name)?;
+        if !utils::file::exists(&entity_dir.join("handler.rs")) {
+            return Err(Error::ResourceNotFound(format!(
+                "handler for '{}' does not exist, run `nebula generate handler {}` first",
+                self.name, self.name
+            )));
+        }
+
+        utils::file::write(
+            &entity_dir.join("routes.rs"),
+            &template::get_router_template()?,
+        )?;
+        utils::file::add_mod_declaration(&entity_dir.join("mod.rs"), "pub mod routes;")?;
+        RouteRegistrar::new(&config)?.register_entity_router(&self.name, &self.prefix)?;
+
+        println!("✅ Generated routes for {}", self.name);
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if !self.prefix.starts_with('/') {
+            return Err(Error::ValidationError(format!(
+                "Invalid route prefix '{}', it must start with '/'",
+                self.prefix
+            )));
+        }
+        Ok(())
+    }
+}
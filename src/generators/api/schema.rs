@@ -0,0 +1,199 @@
+use super::entity::EntityGenerator;
+use super::enums::{self, EnumGenerator};
+use super::field::{EntityField, EnumType, Relation};
+use super::handler::HandlerGenerator;
+use super::relations::{self, MissingSide, ProjectEntity};
+use super::resolver::ResolverGenerator;
+use crate::{
+    config::NebulaConfig,
+    schema::{EntitySchema, Schema, SCHEMA_FILE},
+    utils::{changes, errors::Error},
+};
+use std::path::PathBuf;
+
+/// Generates the enums and the entities of a schema file in one run. Nothing
+/// is written unless every generation succeeds.
+pub struct SchemaGenerator {
+    path: Option<PathBuf>,
+}
+
+impl SchemaGenerator {
+    /// `path` defaults to the `nebula.schema.toml` of the project.
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+
+    pub fn generate(&self) -> Result<(), Error> {
+        let config = NebulaConfig::load()?;
+        let path = self
+            .path
+            .clone()
+            .unwrap_or_else(|| config.root.join(SCHEMA_FILE));
+        let schema = Schema::load(&path)?;
+        let entities = Self::check(&config, &schema)?;
+
+        let failure = format!("{} was not applied", path.display());
+        let written =
+            changes::transaction(&failure, || Self::generate_all(&config, &schema, &entities))?;
+        if let Some(written) = written {
+            let noun = if entities.len() == 1 {
+                "entity"
+            } else {
+                "entities"
+            };
+            println!(
+                "✅ Generated {} {} from {} ({} files written)",
+                entities.len(),
+                noun,
+                path.display(),
+                written.len()
+            );
+        }
+        Ok(())
+    }
+
+    fn generate_all(
+        config: &NebulaConfig,
+        schema: &Schema,
+        entities: &[&EntitySchema],
+    ) -> Result<(), Error> {
+        let shared = enums::load_shared(config)?;
+        for (name, variants) in &schema.enums {
+            if shared.iter().any(|e| &e.name == name) {
+                println!("⏭️  Enum {} already exists", name);
+                continue;
+            }
+            EnumGenerator::new(name.clone(), variants.clone()).generate()?;
+        }
+        let mut generators = Vec::new();
+        for entity in entities {
+            let generator = EntityGenerator::declared(
+                entity.name.clone(),
+                entity.field_specs()?,
+                entity.relations.clone(),
+            );
+            generator.generate()?;
+            generators.push(generator);
+        }
+        // Join tables reference the tables of both sides
+        for generator in &generators {
+            generator.generate_many_to_many()?;
+        }
+        let server_type = config.project.server_type;
+        for entity in entities.iter().filter(|e| e.endpoints) {
+            if server_type.has_rest() {
+                HandlerGenerator::new(entity.name.clone()).generate()?;
+            }
+            if server_type.has_graphql() {
+                ResolverGenerator::new(entity.name.clone()).generate()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses every field and checks the relations of the schema against
+    /// each other and the project, before anything is generated. Returns the
+    /// entities in the order of their tables' foreign keys.
+    fn check<'a>(
+        config: &NebulaConfig,
+        schema: &'a Schema,
+    ) -> Result<Vec<&'a EntitySchema>, Error> {
+        let mut enums = enums::load_shared(config)?;
+        for (name, variants) in &schema.enums {
+            if !enums.iter().any(|e| &e.name == name) {
+                enums.push(
+                    EnumType::new(name, variants.clone(), true)
+                        .map_err(|e| Error::ValidationError(format!("enum {}: {}", name, e)))?,
+                );
+            }
+        }
+
+        let mut declared = Vec::new();
+        for entity in &schema.entities {
+            let mut fields = Vec::new();
+            for spec in entity.field_specs()?.iter().chain(entity.relations.iter()) {
+                fields.push(EntityField::new(spec, &entity.name, &enums)?);
+            }
+            declared.push(ProjectEntity {
+                name: entity.name.clone(),
+                relations: fields
+                    .into_iter()
+                    .filter(|f| f.relation.is_some())
+                    .collect(),
+            });
+        }
+        let project = relations::scan(config)?
+            .into_iter()
+            .filter(|e| !schema.entities.iter().any(|s| s.name == e.name))
+            .chain(declared)
+            .collect::<Vec<_>>();
+
+        for entity in &schema.entities {
+            let Some(declared) = project.iter().find(|e| e.name == entity.name) else {
+                continue;
+            };
+            let fields = declared.relations.iter().collect::<Vec<_>>();
+            for side in relations::check(&entity.name, &fields, &project)? {
+                match side {
+                    MissingSide::Entity { name, .. } => {
+                        return Err(Error::InvalidRelationType(format!(
+                            "{} points to {}, which is neither in {} nor in the project",
+                            entity.name, name, SCHEMA_FILE
+                        )));
+                    }
+                    // The foreign key of a has_one or has_many is on the other side
+                    MissingSide::Inverse {
+                        entity: target,
+                        inverse,
+                    } if inverse.contains(":belongs_to:") => {
+                        return Err(Error::InvalidRelationType(format!(
+                            "{} has no foreign key to {}, add {} to its relations",
+                            target,
+                            entity.name,
+                            inverse.trim_end_matches('?')
+                        )));
+                    }
+                    MissingSide::Inverse { .. } => {}
+                }
+            }
+        }
+
+        Ok(Self::order(&schema.entities, &project))
+    }
+
+    /// The entities after the ones their tables reference, in the order of
+    /// the schema otherwise. Entities referencing each other keep that order.
+    /// A many-to-many relation references the tables from its join table,
+    /// created after all of them.
+    fn order<'a>(entities: &'a [EntitySchema], project: &[ProjectEntity]) -> Vec<&'a EntitySchema> {
+        let references = |entity: &EntitySchema| -> Vec<&str> {
+            let Some(declared) = project.iter().find(|e| e.name == entity.name) else {
+                return Vec::new();
+            };
+            declared
+                .relations
+                .iter()
+                .filter_map(|f| match &f.relation {
+                    Some(Relation::BelongsTo(target)) => Some(target.as_str()),
+                    _ => None,
+                })
+                .filter(|target| *target != entity.name)
+                .collect()
+        };
+
+        let mut remaining = entities.iter().collect::<Vec<_>>();
+        let mut ordered: Vec<&EntitySchema> = Vec::new();
+        while !remaining.is_empty() {
+            let ready = remaining
+                .iter()
+                .position(|entity| {
+                    references(entity)
+                        .iter()
+                        .all(|target| !remaining.iter().any(|e| e.name == *target))
+                })
+                .unwrap_or(0);
+            ordered.push(remaining.remove(ready));
+        }
+        ordered
+    }
+}
@@ -1,17 +1,121 @@
+use std::path::Path;
+
+use crate::template;
+use crate::types::FrontendType;
 use crate::utils::{errors::Error, file};
 
-pub struct WebGenerator;
+const DEFAULT_APP_HOST: &str = "127.0.0.1";
+const DEFAULT_APP_PORT: &str = "3000";
+
+pub struct WebGenerator {
+    name: String,
+    web_dir: String,
+    env_file: String,
+    frontend: FrontendType,
+    workspace: bool,
+}
 
 impl WebGenerator {
-    pub fn generate(project_dir: &str) -> Result<(), Error> {
-        let component = r#"
-        fn main() {
-            println!("Hello, world!");
+    pub fn new(
+        name: &str,
+        web_dir: &str,
+        env_file: &str,
+        frontend: FrontendType,
+        workspace: bool,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            web_dir: web_dir.to_string(),
+            env_file: env_file.to_string(),
+            frontend,
+            workspace,
         }
-        "#;
+    }
 
-        file::create_file_in_dir(project_dir, "component.rs", component)?;
-        println!("Client généré avec succès !");
+    pub fn generate(&self) -> Result<(), Error> {
+        self.init_cargo()?;
+        self.init_trunk()?;
+        self.init_index()?;
+        self.init_main()?;
+        self.init_app()?;
+        self.init_components()?;
+        self.init_pages()?;
+
+        println!("✅ Generated {} frontend", self.frontend.as_str());
         Ok(())
     }
+
+    fn src_dir(&self, sub_dir: &str) -> String {
+        format!("{}/src{}", self.web_dir, sub_dir)
+    }
+
+    fn init_cargo(&self) -> Result<(), Error> {
+        let crate_name = Path::new(&self.web_dir)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or(Error::InvalidPath)?;
+
+        let content = template::get_web_cargo_template(crate_name, self.frontend, self.workspace)?;
+        file::create_file_in_dir(&self.web_dir, "Cargo.toml", &content)
+    }
+
+    /// The dev server address comes from the `APP_HOST`/`APP_PORT` of the
+    /// project `.env`, so it must be written before the frontend.
+    fn init_trunk(&self) -> Result<(), Error> {
+        let env = file::read_env(Path::new(&self.env_file))?;
+        let host = env
+            .get("APP_HOST")
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_APP_HOST);
+        let port = env
+            .get("APP_PORT")
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_APP_PORT);
+
+        if port.parse::<u16>().is_err() {
+            return Err(Error::ConfigError(format!(
+                "{}: APP_PORT must be a valid port number, got '{}'",
+                self.env_file, port
+            )));
+        }
+
+        let content = template::get_trunk_template(host, port)?;
+        file::create_file_in_dir(&self.web_dir, "Trunk.toml", &content)
+    }
+
+    fn init_index(&self) -> Result<(), Error> {
+        let content = template::get_web_index_template(&self.name)?;
+        file::create_file_in_dir(&self.web_dir, "index.html", &content)
+    }
+
+    fn init_main(&self) -> Result<(), Error> {
+        let content = template::get_web_main_template(self.frontend)?;
+        file::create_file_in_dir(&self.src_dir(""), "main.rs", &content)
+    }
+
+    fn init_app(&self) -> Result<(), Error> {
+        let content = template::get_web_app_template(self.frontend)?;
+        file::create_file_in_dir(&self.src_dir(""), "app.rs", &content)
+    }
+
+    fn init_components(&self) -> Result<(), Error> {
+        let dir = self.src_dir("/components");
+        let layout = template::get_web_layout_template(&self.name, self.frontend)?;
+        file::create_file_in_dir(&dir, "mod.rs", "pub mod layout;\n")?;
+        file::create_file_in_dir(&dir, "layout.rs", &layout)
+    }
+
+    fn init_pages(&self) -> Result<(), Error> {
+        let dir = self.src_dir("/pages");
+        let home = template::get_web_page_template(
+            "Home",
+            &format!("Welcome to {}", self.name),
+            self.frontend,
+        )?;
+        let not_found =
+            template::get_web_page_template("NotFound", "Page not found", self.frontend)?;
+        file::create_file_in_dir(&dir, "mod.rs", "pub mod home;\npub mod not_found;\n")?;
+        file::create_file_in_dir(&dir, "home.rs", &home)?;
+        file::create_file_in_dir(&dir, "not_found.rs", &not_found)
+    }
 }
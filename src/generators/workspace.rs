@@ -0,0 +1,50 @@
+use crate::template;
+use crate::utils::{errors::Error, file};
+
+pub const WORKSPACE_MEMBERS: &[&str] = &["api", "web", "shared"];
+
+/// Root of a full project: the workspace Cargo.toml and the `shared` crate
+/// holding the DTOs used by both the api and web members.
+pub struct WorkspaceGenerator {
+    project_dir: String,
+}
+
+impl WorkspaceGenerator {
+    pub fn new(project_dir: &str) -> Self {
+        Self {
+            project_dir: project_dir.to_string(),
+        }
+    }
+
+    pub fn generate(&self) -> Result<(), Error> {
+        self.init_cargo()?;
+        self.init_shared()?;
+
+        Ok(())
+    }
+
+    fn init_cargo(&self) -> Result<(), Error> {
+        let content = template::get_workspace_cargo_template(WORKSPACE_MEMBERS)?;
+        file::create_file_in_dir(&self.project_dir, "Cargo.toml", &content)?;
+
+        println!("✅ Generated workspace Cargo.toml file");
+        Ok(())
+    }
+
+    fn init_shared(&self) -> Result<(), Error> {
+        let shared_dir = format!("{}/shared", self.project_dir);
+        file::create_file_in_dir(
+            &shared_dir,
+            "Cargo.toml",
+            &template::get_shared_cargo_template()?,
+        )?;
+        file::create_file_in_dir(
+            &format!("{}/src", shared_dir),
+            "lib.rs",
+            &template::get_shared_lib_template()?,
+        )?;
+
+        println!("✅ Generated shared crate");
+        Ok(())
+    }
+}
@@ -1,24 +1,60 @@
 mod cli;
 mod commands;
+mod config;
 mod generators;
+mod schema;
 mod template;
 mod types;
 mod utils;
 
 use clap::Parser;
 use cli::Cli;
-use commands::{generate::GenerateCommand, new::NewCommand};
+use commands::{
+    destroy::DestroyCommand, generate::GenerateCommand, new::NewCommand, scaffold::ScaffoldCommand,
+};
+use utils::{changes, errors::Error};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
+    if let Some(dir) = &cli.project_dir {
+        if let Err(e) = std::env::set_current_dir(dir) {
+            let e = Error::InvalidOptions(format!(
+                "Impossible d'utiliser le répertoire {} : {}",
+                dir.display(),
+                e
+            ));
+            eprintln!("{}", e);
+            return Err(Box::new(e));
+        }
+    }
+
+    if cli.dry_run {
+        changes::enable_dry_run();
+    }
+    if cli.force {
+        changes::enable_force();
+    }
+
     if let Err(e) = match cli.command {
         cli::Commands::New(args) => NewCommand::run(args),
-        cli::Commands::Generate { opts } => GenerateCommand::run(opts),
+        cli::Commands::Scaffold(args) => ScaffoldCommand::run(args),
+        cli::Commands::Generate {
+            opts: Some(opts), ..
+        } => GenerateCommand::run(opts),
+        cli::Commands::Generate {
+            opts: None,
+            from_schema,
+        } => GenerateCommand::from_schema(from_schema),
+        cli::Commands::Destroy { opts } => DestroyCommand::run(opts),
     } {
         eprintln!("{}", e);
         return Err(Box::new(e));
     }
 
+    if cli.dry_run {
+        changes::print_summary();
+    }
+
     Ok(())
 }
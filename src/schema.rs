@@ -0,0 +1,166 @@
+//! The declarative schema of a project, `nebula.schema.toml`:
+//!
+//! ```toml
+//! [enums]
+//! Status = ["Draft", "Published"]
+//!
+//! [[entity]]
+//! name = "User"
+//! fields = ["name:String|required", "email:String|email|unique"]
+//!
+//! [[entity]]
+//! name = "Post"
+//! fields = [
+//!     "title:String|required|max_length=200",
+//!     { name = "views", type = "i64", default = 0 },
+//!     "status:Status=Draft",
+//! ]
+//! relations = ["author:belongs_to:User", "tags:many_to_many:Tag"]
+//! ```
+//!
+//! A field is written as with `-f`, or as a table of its parts. `endpoints =
+//! false` leaves out the handler and the resolver of an entity.
+
+use crate::utils::errors::Error;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const SCHEMA_FILE: &str = "nebula.schema.toml";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    /// Enums of `src/enums/`, by name.
+    #[serde(default)]
+    pub enums: BTreeMap<String, Vec<String>>,
+    #[serde(default, rename = "entity")]
+    pub entities: Vec<EntitySchema>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntitySchema {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Vec<FieldSchema>,
+    #[serde(default)]
+    pub relations: Vec<String>,
+    /// Generate the handler and the resolver, as the server type allows.
+    #[serde(default = "default_endpoints")]
+    pub endpoints: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FieldSchema {
+    /// `title:String|required`, as given to `-f`.
+    Spec(String),
+    Table(FieldTable),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldTable {
+    pub name: String,
+    #[serde(rename = "type", default = "default_type")]
+    pub field_type: String,
+    #[serde(default)]
+    pub optional: bool,
+    pub default: Option<toml::Value>,
+    #[serde(default)]
+    pub rules: Vec<String>,
+}
+
+fn default_endpoints() -> bool {
+    true
+}
+
+fn default_type() -> String {
+    "String".to_string()
+}
+
+impl Schema {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        if !path.is_file() {
+            return Err(Error::ResourceNotFound(format!(
+                "{} does not exist",
+                path.display()
+            )));
+        }
+        let content = std::fs::read_to_string(path)?;
+        let schema: Self = toml::from_str(&content)
+            .map_err(|e| Error::ConfigError(format!("{}: {}", path.display(), e)))?;
+        schema.validate()?;
+        Ok(schema)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        for (i, entity) in self.entities.iter().enumerate() {
+            if self.entities[..i].iter().any(|e| e.name == entity.name) {
+                return Err(Error::ConfigError(format!(
+                    "entity {} is declared twice",
+                    entity.name
+                )));
+            }
+            if self.enums.contains_key(&entity.name) {
+                return Err(Error::ConfigError(format!(
+                    "{} is declared both as an entity and as an enum",
+                    entity.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EntitySchema {
+    /// The fields in the syntax of `-f`.
+    pub fn field_specs(&self) -> Result<Vec<String>, Error> {
+        self.fields.iter().map(FieldSchema::to_spec).collect()
+    }
+}
+
+impl FieldSchema {
+    fn to_spec(&self) -> Result<String, Error> {
+        let table = match self {
+            FieldSchema::Spec(spec) => return Ok(spec.clone()),
+            FieldSchema::Table(table) => table,
+        };
+
+        let mut spec = format!("{}:{}", table.name, table.field_type);
+        if table.optional {
+            spec.push('?');
+        }
+        if let Some(default) = &table.default {
+            let value = match default {
+                // Quoted so that the spaces around a string are kept
+                toml::Value::String(text) if table.field_type == "String" => {
+                    format!("\"{}\"", text)
+                }
+                toml::Value::String(text) => text.clone(),
+                toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => {
+                    default.to_string()
+                }
+                _ => {
+                    return Err(Error::InvalidFieldFormat(format!(
+                        "{}: the default must be a string, a number or a boolean",
+                        table.name
+                    )))
+                }
+            };
+            spec.push_str(&format!("={}", escape(&value)));
+        }
+        for rule in &table.rules {
+            spec.push_str(&format!("|{}", escape(rule)));
+        }
+        Ok(spec)
+    }
+}
+
+/// Escapes the characters that end a default or a rule in a field spec.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(',', "\\,")
+}
@@ -1,364 +1,693 @@
-pub fn get_env_template(name: &str) -> String {
-    format!(
-        r#"# Database Configuration
-DB_HOST=127.0.0.1
-DB_PORT=5432
-DB_USER=postgres
-DB_PASSWORD=postgres
-DB_NAME={name}
-
-# Api Configuration
-SERVER_HOST=127.0.0.1
-SERVER_PORT=8080
-API_VERSION=v1
-LOG_LEVEL=debug
-
-# Web Configuration
-APP_HOST=127.0.0.1
-APP_PORT=3000
-NODE_ENV=development"#,
-        name = name,
+use crate::config::NebulaConfig;
+use crate::types::{DatabaseType, FrontendType, ServerType};
+use crate::utils::{errors::Error, tools};
+use convert_case::{Case, Casing};
+use minijinja::{context, AutoEscape, Environment, UndefinedBehavior, Value};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+macro_rules! embed_templates {
+    ($($name:literal),* $(,)?) => {
+        &[$(($name, include_str!(concat!("templates/", $name, ".tpl")))),*]
+    };
+}
+
+/// Templates compiled into the binary, used when neither the project nor the
+/// user provides a file with the same name.
+const DEFAULT_TEMPLATES: &[(&str, &str)] = embed_templates![
+    "partials/package.toml",
+    "partials/enum.rs",
+    "partials/field.rs",
+    "project/env",
+    "project/gitignore",
+    "project/nebula.config.toml",
+    "project/README.md",
+    "workspace/Cargo.toml",
+    "workspace/shared/Cargo.toml",
+    "workspace/shared/lib.rs",
+    "api/Cargo.toml",
+    "api/main.rs",
+    "api/server.rs",
+    "api/middleware.rs",
+    "api/route.rs",
+    "api/database/sql.rs",
+    "api/database/mongodb.rs",
+    "api/schema.rs",
+    "entity/entity.rs",
+    "entity/repository/sql.rs",
+    "entity/repository/mongodb.rs",
+    "entity/handler.rs",
+    "entity/routes.rs",
+    "entity/resolver.rs",
+    "enum/enum.rs",
+    "relation/many_to_many.rs",
+    "relation/relations.rs",
+    "route/entity_routes",
+    "route/nested_router",
+    "route/graphql",
+    "route/relation_routes",
+    "migration/create_table.sql",
+    "migration/drop_table.sql",
+    "migration/create_enum.sql",
+    "migration/drop_enum.sql",
+    "migration/add_column.sql",
+    "migration/drop_column.sql",
+    "migration/add_unique_index.sql",
+    "migration/drop_index.sql",
+    "migration/empty.sql",
+    "web/Cargo.toml",
+    "web/Trunk.toml",
+    "web/index.html",
+    "web/main.rs",
+    "web/leptos/app.rs",
+    "web/leptos/layout.rs",
+    "web/leptos/page.rs",
+    "web/yew/app.rs",
+    "web/yew/layout.rs",
+    "web/yew/page.rs",
+];
+
+/// A field of a generated entity, as seen by the entity templates.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldContext {
+    pub name: String,
+    /// Type of the field in the entity struct.
+    #[serde(rename = "type")]
+    pub field_type: String,
+    /// Type of the field without the `Option` of an optional field.
+    pub inner_type: String,
+    pub optional: bool,
+    /// Rust expression of the default value.
+    pub default: Option<String>,
+    /// Arguments of the `#[validate(...)]` attribute of the field.
+    pub validations: Vec<String>,
+    /// Regular expression the field must match, declared as a static.
+    pub pattern: Option<String>,
+    /// Stored as JSON in SQL databases.
+    pub json: bool,
+}
+
+/// A column written by a SQL repository.
+#[derive(Debug, Clone)]
+pub struct ColumnContext {
+    pub name: String,
+    /// Bound through `sqlx::types::Json`, as `FieldContext::json`.
+    pub json: bool,
+    pub optional: bool,
+}
+
+/// A relation of a generated entity, loaded by its `relations.rs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelationContext {
+    pub name: String,
+    /// `has_one`, `has_many`, `belongs_to` or `many_to_many`.
+    pub kind: String,
+    pub target: String,
+    pub target_table: String,
+    /// Type returned by the loader, as in `Vec<Post>`.
+    pub loaded_type: String,
+    /// Column referencing the other side: in the entity's table for a
+    /// `belongs_to`, in the target's table for a `has_one` or `has_many`.
+    pub foreign_key: Option<String>,
+    /// A nullable `belongs_to`.
+    pub optional: bool,
+}
+
+/// An enum of a generated entity or of `src/enums/`.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnumContext {
+    pub name: String,
+    /// Name of the PostgreSQL type.
+    pub type_name: String,
+    pub variants: Vec<String>,
+    /// Values stored in the database, one per variant.
+    pub values: Vec<String>,
+    /// Declared in `src/enums/` and imported by the entity.
+    pub shared: bool,
+}
+
+/// One side of a many-to-many relation, as seen from the entity `name`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JoinContext {
+    pub name: String,
+    pub target: String,
+    /// Accessor of the related entities, also the name of the module.
+    pub field: String,
+    pub join_table: String,
+    pub target_table: String,
+    /// Column of the join table referencing `name`.
+    pub source_column: String,
+    /// Column of the join table referencing `target`.
+    pub target_column: String,
+    /// The join table is the table of a `through` entity.
+    pub through: bool,
+    /// The declaring side also gets REST handlers and GraphQL objects.
+    pub endpoints: bool,
+}
+
+/// Directories searched for `<name>.tpl` overrides, in order: the project's
+/// `[paths] templates`, then `~/.config/nebula/templates`.
+fn override_dirs() -> &'static [PathBuf] {
+    static OVERRIDE_DIRS: OnceLock<Vec<PathBuf>> = OnceLock::new();
+    OVERRIDE_DIRS.get_or_init(|| {
+        let mut found = Vec::new();
+        let config = std::env::current_dir().ok().and_then(|cwd| {
+            NebulaConfig::find_root(&cwd).and_then(|root| NebulaConfig::load_from(root).ok())
+        });
+        if let Some(config) = config {
+            found.push(config.templates_path());
+        }
+        if let Some(home) = dirs::home_dir() {
+            found.push(home.join(".config/nebula/templates"));
+        }
+        found
+    })
+}
+
+fn load(name: &str) -> Result<Option<String>, minijinja::Error> {
+    let file_name = format!("{}.tpl", name);
+    for dir in override_dirs() {
+        let path = dir.join(&file_name);
+        if path.is_file() {
+            return std::fs::read_to_string(&path).map(Some).map_err(|e| {
+                minijinja::Error::new(
+                    minijinja::ErrorKind::InvalidOperation,
+                    format!("could not read {}", path.display()),
+                )
+                .with_source(e)
+            });
+        }
+    }
+
+    Ok(DEFAULT_TEMPLATES
+        .iter()
+        .find(|(default, _)| *default == name)
+        .map(|(_, content)| content.to_string()))
+}
+
+/// Template environment: generated code is never escaped, block tags do not
+/// leave blank lines behind and using an undefined variable is an error.
+fn environment() -> &'static Environment<'static> {
+    static ENVIRONMENT: OnceLock<Environment<'static>> = OnceLock::new();
+    ENVIRONMENT.get_or_init(|| {
+        let mut env = Environment::new();
+        env.set_loader(load);
+        env.set_auto_escape_callback(|_| AutoEscape::None);
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        env.set_keep_trailing_newline(true);
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+
+        env.add_filter("snake_case", |value: String| value.to_case(Case::Snake));
+        env.add_filter("camel_case", |value: String| value.to_case(Case::Camel));
+        env.add_filter("pascal_case", |value: String| value.to_case(Case::Pascal));
+        env.add_filter("kebab_case", |value: String| value.to_case(Case::Kebab));
+        env.add_filter("screaming_snake_case", |value: String| {
+            value.to_case(Case::UpperSnake)
+        });
+        env.add_filter("plural", |value: String| tools::pluralize(&value));
+        env
+    })
+}
+
+/// Renders the template `name` with the variables of `context`.
+fn render(name: &str, context: Value) -> Result<String, Error> {
+    environment()
+        .get_template(name)
+        .and_then(|template| template.render(context))
+        .map_err(|e| template_error(name, e))
+}
+
+/// Points at the template and line the error comes from, which may be a
+/// partial included by `name`.
+fn template_error(name: &str, error: minijinja::Error) -> Error {
+    let file = error.name().unwrap_or(name).to_string();
+    let message = match error.kind() {
+        minijinja::ErrorKind::UndefinedError => {
+            let source = error.line().and_then(|line| {
+                load(&file)
+                    .ok()
+                    .flatten()?
+                    .lines()
+                    .nth(line - 1)
+                    .map(str::trim)
+                    .map(String::from)
+            });
+            match source {
+                Some(source) => format!("unknown variable in `{}`", source),
+                None => "unknown variable".to_string(),
+            }
+        }
+        kind => error
+            .detail()
+            .map(String::from)
+            .unwrap_or_else(|| kind.to_string()),
+    };
+
+    match error.line() {
+        Some(line) => Error::TemplateError(format!("{}:{}: {}", file, line, message)),
+        None => Error::TemplateError(format!("{}: {}", file, message)),
+    }
+}
+
+fn database_settings(name: &str, database: DatabaseType) -> Vec<(&'static str, String)> {
+    let mut settings = Vec::new();
+    if let Some(port) = database.default_port() {
+        settings.push(("DB_HOST", "127.0.0.1".to_string()));
+        settings.push(("DB_PORT", port.to_string()));
+    }
+    if !database.default_user().is_empty() {
+        settings.push(("DB_USER", database.default_user().to_string()));
+        settings.push(("DB_PASSWORD", database.default_user().to_string()));
+    }
+    settings.push(("DB_NAME", name.to_string()));
+    settings.push(("DATABASE_URL", database.connection_url(name)));
+    settings
+}
+
+pub fn get_env_template(name: &str, database: DatabaseType) -> Result<String, Error> {
+    render(
+        "project/env",
+        context! { database_settings => database_settings(name, database) },
     )
 }
 
-pub fn get_gitignore_template() -> String {
-    r#"# Dependencies
-/node_modules
-/target
-/dist
-/build
-/.pnp
-.pnp.js
-cargo.lock
-
-# Testing
-/coverage
-
-# Production
-/build
-
-# Environment Variables
-.env
-.env.*
-!.env.example
-
-# IDE - VSCode
-.vscode/*
-!.vscode/settings.json
-!.vscode/tasks.json
-!.vscode/launch.json
-!.vscode/extensions.json
-
-# IDE - IntelliJ
-.idea/
-*.iml
-*.iws
-
-# IDE - Vim
-*.swp
-*.swo
-
-# Logs
-logs
-*.log
-npm-debug.log*
-yarn-debug.log*
-yarn-error.log*
-
-# OS
-.DS_Store
-.DS_Store?
-._*
-.Spotlight-V100
-.Trashes
-ehthumbs.db
-Thumbs.db
-
-# Database
-*.sqlite
-*.sqlite3
-*.db
-/database
-
-# Rust specific
-debug/
-target/
-**/*.rs.bk
-Cargo.lock
-
-# Generated documentation
-/doc"#
-        .to_string()
+pub fn get_gitignore_template() -> Result<String, Error> {
+    render("project/gitignore", context! {})
 }
 
 pub fn get_nebula_template(
     name: &str,
     project_type: &str,
-    database: &str,
+    database: DatabaseType,
     server_type: &str,
-) -> String {
-    format!(
-        r#"[project]
-name = "{name}"
-type = "{project_type}"  # web, api, full
-database = "{database}"  # mysql, postgresql, mariadb, mongodb
-server_type = "{server_type}"  # rest, graphql
-
-[paths]
-src = "src"
-api = "src/api"
-web = "src/web"
-migrations = "migrations"
-templates = "templates"
-static = "static"
-tests = "tests"
-
-[environment]
-# Database Configuration
-DB_HOST = "127.0.0.1"
-DB_PORT = "5432"
-DB_USER = "postgres"
-DB_PASSWORD = "postgres"
-DB_NAME = "{name}"
-
-# Backend Configuration
-SERVER_HOST = "127.0.0.1"
-SERVER_PORT = "8080"
-API_VERSION = "v1"
-LOG_LEVEL = "debug"
-
-# Frontend Configuration
-APP_HOST = "127.0.0.1"
-APP_PORT = "3000"
-NODE_ENV = "development"
-
-[deployment]
-provider = "docker"
-container_name = "{name}"
-docker_image = "rust:latest"
-docker_compose = true
-
-[security]
-jwt_enabled = true
-cors_enabled = true
-rate_limit = true
-allowed_origins = ["http://localhost:3000"]"#,
-        name = name,
-        project_type = project_type,
-        database = database,
-        server_type = server_type
+    frontend: &str,
+    api_path: &str,
+    web_path: &str,
+) -> Result<String, Error> {
+    render(
+        "project/nebula.config.toml",
+        context! {
+            name,
+            project_type,
+            database => database.as_str(),
+            database_settings => database_settings(name, database),
+            server_type,
+            frontend,
+            api_path,
+            web_path,
+        },
+    )
+}
+
+pub fn get_readme_template(name: &str, database: DatabaseType) -> Result<String, Error> {
+    render(
+        "project/README.md",
+        context! { name, database => database.label() },
+    )
+}
+
+/// Dependencies declared once in the root Cargo.toml of a full project.
+const WORKSPACE_DEPENDENCIES: &[(&str, &str)] = &[
+    ("serde", r#"{ version = "1.0", features = ["derive"] }"#),
+    ("serde_json", r#""1.0""#),
+    ("chrono", r#"{ version = "0.4", features = ["serde"] }"#),
+    ("shared", r#"{ path = "shared" }"#),
+];
+
+/// Version requirements of the shared dependencies, inherited from the
+/// workspace in a member crate.
+fn dependencies(workspace: bool) -> Value {
+    WORKSPACE_DEPENDENCIES
+        .iter()
+        .map(|(name, spec)| {
+            let spec = if workspace {
+                "{ workspace = true }"
+            } else {
+                spec
+            };
+            (*name, spec)
+        })
+        .collect()
+}
+
+pub fn get_workspace_cargo_template(members: &[&str]) -> Result<String, Error> {
+    render(
+        "workspace/Cargo.toml",
+        context! { members, dependencies => WORKSPACE_DEPENDENCIES },
     )
 }
 
-pub fn get_readme_template(name: &str) -> String {
-    format!(
-        r#"# {name}
+pub fn get_shared_cargo_template() -> Result<String, Error> {
+    render(
+        "workspace/shared/Cargo.toml",
+        context! { name => "shared", workspace => true, dependencies => dependencies(true) },
+    )
+}
 
-## Prerequisites
-- Rust (latest stable)
-- PostgreSQL
-- Node.js (if using frontend)
+pub fn get_shared_lib_template() -> Result<String, Error> {
+    render("workspace/shared/lib.rs", context! {})
+}
 
-## Quick Start
+pub fn get_cargo_template(
+    name: &str,
+    database: DatabaseType,
+    server_type: ServerType,
+    workspace: bool,
+) -> Result<String, Error> {
+    render(
+        "api/Cargo.toml",
+        context! {
+            name,
+            workspace,
+            dependencies => dependencies(workspace),
+            graphql => server_type.has_graphql(),
+            sqlx_feature => database.sqlx_feature(),
+        },
+    )
+}
 
-### Installation
-```bash
-# Clone the repository
-git clone <repository-url>
-cd {name}
+/// `relations` are the relations of the entity, in the syntax of `-r`.
+pub fn get_entity_template(
+    name: &str,
+    fields: &[FieldContext],
+    enums: &[EnumContext],
+    collections: &[&str],
+    relations: &[String],
+    database: DatabaseType,
+    graphql: bool,
+) -> Result<String, Error> {
+    render(
+        "entity/entity.rs",
+        context! {
+            name,
+            fields,
+            enums,
+            collections,
+            relations,
+            graphql,
+            sql => database.is_sql(),
+            postgres => database == DatabaseType::Postgresql,
+        },
+    )
+}
 
-# Install dependencies
-cargo build
+/// A field of the entity struct, added to an existing entity.
+pub fn get_field_template(field: &FieldContext) -> Result<String, Error> {
+    render("partials/field.rs", context! { field })
+}
 
-# Set up environment variables
-cp .env.example .env
+pub fn get_enum_template(
+    enum_type: &EnumContext,
+    database: DatabaseType,
+    graphql: bool,
+) -> Result<String, Error> {
+    render(
+        "enum/enum.rs",
+        context! {
+            enum_type,
+            graphql,
+            sql => database.is_sql(),
+            postgres => database == DatabaseType::Postgresql,
+        },
+    )
+}
 
-# Run migrations
-cargo run -- db migrate
+pub fn get_many_to_many_template(
+    relation: &JoinContext,
+    database: DatabaseType,
+    server_type: ServerType,
+) -> Result<String, Error> {
+    render(
+        "relation/many_to_many.rs",
+        context! {
+            rest => server_type.has_rest(),
+            graphql => server_type.has_graphql(),
+            mysql => database.sqlx_feature() == Some("mysql"),
+            first_placeholder => database.placeholder(1),
+            second_placeholder => database.placeholder(2),
+            ..Value::from_serialize(relation)
+        },
+    )
+}
 
-# Start the server
-cargo run
-"#,
-        name = name,
+pub fn get_relations_template(
+    name: &str,
+    relations: &[RelationContext],
+    database: DatabaseType,
+    graphql: bool,
+) -> Result<String, Error> {
+    render(
+        "relation/relations.rs",
+        context! {
+            name,
+            relations,
+            graphql,
+            sql => database.is_sql(),
+            first_placeholder => database.placeholder(1),
+        },
     )
 }
 
-pub fn get_cargo_template(name: &str) -> String {
-    format!(
-        r#"[package]
-name = "{name}"
-version = "0.1.0"
-edition = "2021"
-
-[dependencies]
-tokio = {{ version = "1.0", features = ["full"] }}
-axum = {{ version = "0.8.0", features = ["macros"] }}
-serde = {{ version = "1.0", features = ["derive"] }}
-serde_json = "1.0"
-tower = "0.5.2"
-tower-http = {{ version = "0.6.2", features = ["cors"] }}
-dotenvy = "0.15"
-tracing = "0.1"
-tracing-subscriber = {{ version = "0.3", features = ["env-filter"] }}
-thiserror = "2.0.10"
-chrono = {{ version = "0.4", features = ["serde"] }}
-validator = {{ version = "0.20.0", features = ["derive"] }}
-async-graphql = {{ version = "5.0.7", features = ["chrono"] }}"#,
-        name = name
+pub fn get_sql_repository_template(
+    name: &str,
+    table: &str,
+    columns: &[ColumnContext],
+    database: DatabaseType,
+) -> Result<String, Error> {
+    let columns = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let bind = match (column.json, column.optional) {
+                (false, _) => format!("&self.{}", column.name),
+                (true, false) => format!("sqlx::types::Json(&self.{})", column.name),
+                // None is stored as NULL rather than as JSON null
+                (true, true) => format!("self.{}.as_ref().map(sqlx::types::Json)", column.name),
+            };
+            context! {
+                name => column.name,
+                placeholder => database.placeholder(i + 1),
+                bind,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    render(
+        "entity/repository/sql.rs",
+        context! {
+            name,
+            table,
+            mysql => database.sqlx_feature() == Some("mysql"),
+            id_placeholder => database.placeholder(columns.len() + 1),
+            first_placeholder => database.placeholder(1),
+            columns,
+        },
     )
 }
 
-pub fn get_entity_template(name: &str, fields: &str) -> String {
-    format!(
-        r#"use serde::{{Deserialize, Serialize}};
-use async_graphql::{{SimpleObject, InputObject}};
-use validator::Validate;
-use chrono::{{DateTime, Utc}};
-
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, Validate, Default)]
-pub struct {name} {{
-    #[serde(default)]
-    pub id: i32,
-{fields}
-    pub created_at: Option<DateTime<Utc>>,
-    pub updated_at: Option<DateTime<Utc>>,
-}}
-
-impl {name} {{
-    pub fn new() -> Self {{
-        Self::default()
-    }}
-
-    pub fn create(&self) -> Result<(), String> {{
-        Ok(())
-    }}
-
-    pub fn update(&self) -> Result<(), String> {{
-        Ok(())
-    }}
-
-    pub fn delete(&self) -> Result<(), String> {{
-        Ok(())
-    }}
-
-    pub fn destroy(&self) -> Result<(), String> {{
-        Ok(())
-    }}
-
-    pub fn find_by_id(id: i32) -> Result<Self, String> {{
-        Ok(Self::new())
-    }}
-}}
-
-#[derive(InputObject, Validate)]
-pub struct {name}Input {{
-{fields}
-}}
-
-#[derive(InputObject)]
-pub struct Update{name}Input {{
-{fields}
-}}"#,
-        name = name,
-        fields = fields
+pub fn get_mongodb_repository_template(name: &str, collection: &str) -> Result<String, Error> {
+    render(
+        "entity/repository/mongodb.rs",
+        context! { name, collection },
     )
 }
 
-pub fn get_main_template() -> String {
-    r#"mod server;
-mod route;
-mod middleware;
-
-use dotenv::dotenv;
-
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
-    
-    // Load environment variables
-    dotenv().ok();
-
-    // Start server
-    server::Server::run().await?;
-    
-    Ok(())
-}
-"#
-    .to_string()
-}
-
-pub fn get_server_template() -> String {
-    r#"use std::env;
-use std::net::SocketAddr;
-use axum::{routing::get, Router};
-use tokio::net::TcpListener;
-use crate::route;
-use crate::middleware;
-
-#[derive(Debug)]
-pub struct Server;
-
-impl Server {
-    pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
-        // Load environment variables
-        let host = env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-        let port: u16 = env::var("SERVER_PORT")
-            .unwrap_or_else(|_| "3000".to_string())
-            .parse()
-            .expect("SERVER_PORT must be a valid integer");
-
-        // Configure server address
-        let addr: SocketAddr = format!("{}:{}", host, port)
-            .parse()
-            .expect("Failed to parse socket address");
-
-        // Setup routes and middleware
-        let app = Router::new()
-            .merge(route::configure())
-            .layer(middleware::cors());
-
-        // Create and bind TCP listener
-        let listener = TcpListener::bind(addr).await?;
-            
-        println!("🚀 Server started on http://{}", addr);
-        
-        // Start server
-        axum::serve(listener, app).await?;
-        Ok(())
-    }
+pub fn get_database_template(
+    database: DatabaseType,
+    migrations_dir: &str,
+) -> Result<String, Error> {
+    let (pool_type, pool_options) = match database {
+        DatabaseType::Postgresql => ("PgPool", "postgres::PgPoolOptions"),
+        DatabaseType::Sqlite => ("SqlitePool", "sqlite::SqlitePoolOptions"),
+        DatabaseType::Mysql | DatabaseType::Mariadb => ("MySqlPool", "mysql::MySqlPoolOptions"),
+        DatabaseType::Mongodb => return render("api/database/mongodb.rs", context! {}),
+    };
+    let pool_options_type = pool_options.rsplit("::").next().unwrap_or(pool_options);
+
+    render(
+        "api/database/sql.rs",
+        context! { pool_type, pool_options, pool_options_type, migrations_dir },
+    )
+}
+
+pub fn get_handler_template(name: &str) -> Result<String, Error> {
+    render("entity/handler.rs", context! { name })
+}
+
+pub fn get_entity_routes_template(base_path: &str, handler: &str) -> Result<String, Error> {
+    render("route/entity_routes", context! { base_path, handler })
+}
+
+pub fn get_nested_router_template(path: &str, module: &str) -> Result<String, Error> {
+    render("route/nested_router", context! { path, module })
+}
+
+pub fn get_router_template() -> Result<String, Error> {
+    render("entity/routes.rs", context! {})
+}
+
+pub fn get_relation_routes_template(
+    base_path: &str,
+    item_path: &str,
+    handler: &str,
+) -> Result<String, Error> {
+    render(
+        "route/relation_routes",
+        context! { base_path, item_path, handler },
+    )
+}
+
+pub fn get_graphql_route_template() -> Result<String, Error> {
+    render("route/graphql", context! {})
+}
+
+pub fn get_resolver_template(name: &str) -> Result<String, Error> {
+    render("entity/resolver.rs", context! { name })
+}
+
+pub fn get_schema_template(queries: &[String], mutations: &[String]) -> Result<String, Error> {
+    render("api/schema.rs", context! { queries, mutations })
+}
+
+/// `enums` are the PostgreSQL types created along with the table.
+pub fn get_create_table_migration_template(
+    table: &str,
+    columns: &[String],
+    enums: &[EnumContext],
+) -> Result<String, Error> {
+    render(
+        "migration/create_table.sql",
+        context! { table, columns, enums },
+    )
+}
+
+pub fn get_drop_table_migration_template(
+    table: &str,
+    enums: &[EnumContext],
+) -> Result<String, Error> {
+    render("migration/drop_table.sql", context! { table, enums })
+}
+
+pub fn get_create_enum_migration_template(enum_type: &EnumContext) -> Result<String, Error> {
+    render("migration/create_enum.sql", context! { enum_type })
+}
+
+pub fn get_drop_enum_migration_template(enum_type: &EnumContext) -> Result<String, Error> {
+    render("migration/drop_enum.sql", context! { enum_type })
+}
+
+pub fn get_add_column_migration_template(table: &str, column: &str) -> Result<String, Error> {
+    render("migration/add_column.sql", context! { table, column })
+}
+
+pub fn get_drop_column_migration_template(table: &str, column: &str) -> Result<String, Error> {
+    render("migration/drop_column.sql", context! { table, column })
+}
+
+pub fn get_add_unique_index_migration_template(
+    table: &str,
+    index: &str,
+    columns: &[&str],
+) -> Result<String, Error> {
+    render(
+        "migration/add_unique_index.sql",
+        context! { table, index, columns },
+    )
+}
+
+pub fn get_drop_index_migration_template(
+    table: &str,
+    index: &str,
+    database: DatabaseType,
+) -> Result<String, Error> {
+    render(
+        "migration/drop_index.sql",
+        context! {
+            table,
+            index,
+            mysql => database.sqlx_feature() == Some("mysql"),
+        },
+    )
+}
+
+pub fn get_empty_migration_template(name: &str, direction: &str) -> Result<String, Error> {
+    render("migration/empty.sql", context! { name, direction })
+}
+
+pub fn get_main_template(server_type: ServerType) -> Result<String, Error> {
+    render(
+        "api/main.rs",
+        context! { graphql => server_type.has_graphql() },
+    )
+}
+
+pub fn get_server_template() -> Result<String, Error> {
+    render("api/server.rs", context! {})
 }
-"#
-    .to_string()
+
+pub fn get_middleware_template() -> Result<String, Error> {
+    render("api/middleware.rs", context! {})
+}
+
+pub fn get_route_template(server_type: ServerType, workspace: bool) -> Result<String, Error> {
+    render(
+        "api/route.rs",
+        context! {
+            rest => server_type.has_rest(),
+            graphql => server_type.has_graphql(),
+            workspace,
+        },
+    )
+}
+
+pub fn get_web_cargo_template(
+    name: &str,
+    frontend: FrontendType,
+    workspace: bool,
+) -> Result<String, Error> {
+    render(
+        "web/Cargo.toml",
+        context! {
+            name,
+            frontend => frontend.as_str(),
+            workspace,
+            dependencies => dependencies(workspace),
+        },
+    )
 }
 
-pub fn get_route_template() -> String {
-    r#"use axum::{
-    routing::{get, post},
-    Router,
-    response::Json,
-};
-use serde_json::json;
+pub fn get_trunk_template(host: &str, port: &str) -> Result<String, Error> {
+    render("web/Trunk.toml", context! { host, port })
+}
 
-pub fn configure() -> Router {
-    Router::new()
-        .route("/", get(health_check))
-        .route("/api/v1/hello", get(hello_world))
+pub fn get_web_index_template(name: &str) -> Result<String, Error> {
+    render("web/index.html", context! { name })
 }
 
-async fn health_check() -> Json<serde_json::Value> {
-    Json(json!({
-        "status": "ok",
-        "version": env!("CARGO_PKG_VERSION"),
-        "name": env!("CARGO_PKG_NAME")
-    }))
+pub fn get_web_main_template(frontend: FrontendType) -> Result<String, Error> {
+    render("web/main.rs", context! { frontend => frontend.as_str() })
 }
 
-async fn hello_world() -> Json<serde_json::Value> {
-    Json(json!({
-        "message": "Hello, World!"
-    }))
+pub fn get_web_app_template(frontend: FrontendType) -> Result<String, Error> {
+    render(&format!("web/{}/app.rs", frontend.as_str()), context! {})
 }
-"#
-    .to_string()
+
+pub fn get_web_layout_template(name: &str, frontend: FrontendType) -> Result<String, Error> {
+    render(
+        &format!("web/{}/layout.rs", frontend.as_str()),
+        context! { name },
+    )
+}
+
+pub fn get_web_page_template(
+    component: &str,
+    title: &str,
+    frontend: FrontendType,
+) -> Result<String, Error> {
+    render(
+        &format!("web/{}/page.rs", frontend.as_str()),
+        context! { component, title },
+    )
 }
@@ -0,0 +1,27 @@
+{% include "partials/package.toml" %}
+
+[dependencies]
+tokio = { version = "1.0", features = ["full"] }
+axum = { version = "0.8.0", features = ["macros"] }
+serde = {{ dependencies.serde }}
+serde_json = {{ dependencies.serde_json }}
+tower = "0.5.2"
+tower-http = { version = "0.6.2", features = ["cors"] }
+dotenvy = "0.15"
+tracing = "0.1"
+tracing-subscriber = { version = "0.3", features = ["env-filter"] }
+thiserror = "2.0.10"
+chrono = {{ dependencies.chrono }}
+validator = { version = "0.20.0", features = ["derive"] }
+{% if workspace %}
+shared = {{ dependencies.shared }}
+{% endif %}
+{% if graphql %}
+async-graphql = { version = "5.0.7", features = ["chrono"] }
+{% endif %}
+{% if sqlx_feature %}
+sqlx = { version = "0.8", features = ["runtime-tokio", "{{ sqlx_feature }}", "chrono"] }
+{% else %}
+mongodb = "3.1"
+futures = "0.3"
+{% endif %}
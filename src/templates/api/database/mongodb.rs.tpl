@@ -0,0 +1,18 @@
+use std::{env, sync::OnceLock};
+
+use mongodb::{Client, Database};
+
+static DATABASE: OnceLock<Database> = OnceLock::new();
+
+pub async fn init() -> Result<(), mongodb::error::Error> {
+    let url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let name = env::var("DB_NAME").expect("DB_NAME must be set");
+
+    let client = Client::with_uri_str(&url).await?;
+    DATABASE.get_or_init(|| client.database(&name));
+    Ok(())
+}
+
+pub fn database() -> &'static Database {
+    DATABASE.get().expect("database is not initialized")
+}
@@ -0,0 +1,21 @@
+use std::{env, sync::OnceLock};
+
+use sqlx::{ {{- pool_options }}, {{ pool_type }}};
+
+static POOL: OnceLock<{{ pool_type }}> = OnceLock::new();
+
+pub async fn init() -> Result<(), Box<dyn std::error::Error>> {
+    let url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = {{ pool_options_type }}::new()
+        .max_connections(5)
+        .connect(&url)
+        .await?;
+
+    sqlx::migrate!("{{ migrations_dir }}").run(&pool).await?;
+    POOL.get_or_init(|| pool);
+    Ok(())
+}
+
+pub fn pool() -> &'static {{ pool_type }} {
+    POOL.get().expect("database pool is not initialized")
+}
@@ -0,0 +1,26 @@
+mod server;
+mod route;
+mod middleware;
+mod database;
+{% if graphql %}
+mod schema;
+{% endif %}
+
+use dotenvy::dotenv;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize logging
+    tracing_subscriber::fmt::init();
+    
+    // Load environment variables
+    dotenv().ok();
+
+    // Connect to the database
+    database::init().await?;
+
+    // Start server
+    server::Server::run().await?;
+    
+    Ok(())
+}
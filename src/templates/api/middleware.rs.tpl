@@ -0,0 +1,23 @@
+use std::env;
+use axum::http::{HeaderValue, Method};
+use tower_http::cors::{Any, CorsLayer};
+
+// Allows the frontend served on APP_HOST:APP_PORT to call the API
+pub fn cors() -> CorsLayer {
+    let host = env::var("APP_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = env::var("APP_PORT").unwrap_or_else(|_| "3000".to_string());
+    let origin: HeaderValue = format!("http://{}:{}", host, port)
+        .parse()
+        .expect("APP_HOST and APP_PORT must form a valid origin");
+
+    CorsLayer::new()
+        .allow_origin(origin)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
+        .allow_headers(Any)
+}
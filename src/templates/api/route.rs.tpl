@@ -0,0 +1,42 @@
+use axum::{
+    routing::get,
+    Router,
+    response::Json,
+};
+
+pub fn configure() -> Router {
+    Router::new()
+        .route("/", get(health_check))
+{% if rest %}
+        .route("/api/v1/hello", get(hello_world))
+{% endif %}
+{% if graphql %}
+{% include "route/graphql" %}
+{% endif %}
+}
+
+{% if workspace %}
+async fn health_check() -> Json<shared::HealthStatus> {
+    Json(shared::HealthStatus {
+        status: "ok".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        name: env!("CARGO_PKG_NAME").to_string(),
+    })
+}
+{% else %}
+async fn health_check() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "ok",
+        "version": env!("CARGO_PKG_VERSION"),
+        "name": env!("CARGO_PKG_NAME")
+    }))
+}
+{% endif %}
+{% if rest %}
+
+async fn hello_world() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "message": "Hello, World!"
+    }))
+}
+{% endif %}
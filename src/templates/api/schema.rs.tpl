@@ -0,0 +1,58 @@
+// Generated by nebula: this file is rewritten every time a resolver is generated.
+use std::sync::OnceLock;
+
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{EmptySubscription, MergedObject, Object, Request, Response, Schema};
+use axum::{response::Html, Json};
+
+#[derive(Default)]
+pub struct ApiQuery;
+
+#[Object]
+impl ApiQuery {
+    async fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+}
+
+#[derive(MergedObject, Default)]
+pub struct QueryRoot(
+    ApiQuery,
+{% for query in queries %}
+    {{ query }},
+{% endfor %}
+);
+
+{% if mutations %}
+#[derive(MergedObject, Default)]
+pub struct MutationRoot(
+{% for mutation in mutations %}
+    {{ mutation }},
+{% endfor %}
+);
+{% else %}
+pub type MutationRoot = async_graphql::EmptyMutation;
+{% endif %}
+
+pub type AppSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+static SCHEMA: OnceLock<AppSchema> = OnceLock::new();
+
+pub fn schema() -> &'static AppSchema {
+    SCHEMA.get_or_init(|| {
+        Schema::build(
+            QueryRoot::default(),
+            MutationRoot::default(),
+            EmptySubscription,
+        )
+        .finish()
+    })
+}
+
+pub async fn graphql_handler(Json(request): Json<Request>) -> Json<Response> {
+    Json(schema().execute(request).await)
+}
+
+pub async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
@@ -0,0 +1,39 @@
+use std::env;
+use std::net::SocketAddr;
+use axum::Router;
+use tokio::net::TcpListener;
+use crate::route;
+use crate::middleware;
+
+#[derive(Debug)]
+pub struct Server;
+
+impl Server {
+    pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
+        // Load environment variables
+        let host = env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let port: u16 = env::var("SERVER_PORT")
+            .unwrap_or_else(|_| "3000".to_string())
+            .parse()
+            .expect("SERVER_PORT must be a valid integer");
+
+        // Configure server address
+        let addr: SocketAddr = format!("{}:{}", host, port)
+            .parse()
+            .expect("Failed to parse socket address");
+
+        // Setup routes and middleware
+        let app = Router::new()
+            .merge(route::configure())
+            .layer(middleware::cors());
+
+        // Create and bind TCP listener
+        let listener = TcpListener::bind(addr).await?;
+            
+        println!("🚀 Server started on http://{}", addr);
+        
+        // Start server
+        axum::serve(listener, app).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+{% if graphql %}
+use async_graphql::{SimpleObject, InputObject{% if enums|rejectattr("shared")|list %}, Enum{% endif %}};
+{% endif %}
+use validator::Validate;
+use chrono::{DateTime, Utc};
+{% if collections %}
+{% if collections|length == 1 %}
+use std::collections::{{ collections[0] }};
+{% else %}
+use std::collections::{{ "{" ~ collections|join(", ") ~ "}" }};
+{% endif %}
+{% endif %}
+{% for enum_type in enums if enum_type.shared %}
+use crate::enums::{{ enum_type.name }};
+{% endfor %}
+{% set patterns = fields|selectattr("pattern")|list %}
+{% if patterns %}
+use regex::Regex;
+use std::sync::LazyLock;
+
+{% for field in patterns %}
+static {{ field.name|screaming_snake_case }}_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"{{ field.pattern }}"#).expect("invalid regex for {{ field.name }}"));
+{% endfor %}
+{% endif %}
+{% for enum_type in enums if not enum_type.shared %}
+
+{% include "partials/enum.rs" %}
+{% endfor %}
+
+{% if relations %}
+// Relations: {{ relations|join(", ") }}
+{% endif %}
+#[derive(Debug, Clone, Serialize, Deserialize, {% if graphql %}SimpleObject, {% endif %}Validate, Default{% if sql %}, sqlx::FromRow{% endif %})]
+{% if graphql and relations %}
+#[graphql(complex)]
+{% endif %}
+pub struct {{ name }} {
+    #[serde(default)]
+    pub id: i32,
+{% for field in fields %}
+{% include "partials/field.rs" %}
+{% endfor %}
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl {{ name }} {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies the fields set in `input`, leaving the others unchanged.
+    pub fn apply(&mut self, input: Update{{ name }}Input) {
+{% for field in fields %}
+        if let Some({{ field.name }}) = input.{{ field.name }} {
+            self.{{ field.name }} = {% if field.optional %}Some({{ field.name }}){% else %}{{ field.name }}{% endif %};
+        }
+{% endfor %}
+    }
+}
+{% for field in fields if field.default %}
+
+fn default_{{ field.name }}() -> {{ field.type }} {
+    {{ field.default }}
+}
+{% endfor %}
+
+impl From<{{ name }}Input> for {{ name }} {
+    fn from(input: {{ name }}Input) -> Self {
+        Self {
+{% for field in fields %}
+            {{ field.name }}: input.{{ field.name }},
+{% endfor %}
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Deserialize, {% if graphql %}InputObject, {% endif %}Validate)]
+pub struct {{ name }}Input {
+{% for field in fields %}
+{% if field.validations %}
+    #[validate({{ field.validations|join(", ") }})]
+{% endif %}
+{% if field.default %}
+    #[serde(default = "default_{{ field.name }}")]
+{% if graphql %}
+    #[graphql(default_with = "default_{{ field.name }}()")]
+{% endif %}
+{% endif %}
+    pub {{ field.name }}: {{ field.type }},
+{% endfor %}
+}
+
+/// Every field is optional: fields left out are not updated, the others are
+/// checked against the same rules as on creation.
+#[derive(Deserialize, {% if graphql %}InputObject, {% endif %}Validate)]
+pub struct Update{{ name }}Input {
+{% for field in fields %}
+{% set validations = field.validations|reject("eq", "required")|list %}
+{% if validations %}
+    #[validate({{ validations|join(", ") }})]
+{% endif %}
+    pub {{ field.name }}: Option<{{ field.inner_type }}>,
+{% endfor %}
+}
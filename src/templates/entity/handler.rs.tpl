@@ -0,0 +1,63 @@
+use axum::{extract::Path, http::StatusCode, Json};
+use validator::Validate;
+
+use super::entity::{ {{- name }}, {{ name }}Input, Update{{ name }}Input};
+
+type HandlerResult<T> = Result<T, (StatusCode, String)>;
+
+pub async fn list() -> HandlerResult<Json<Vec<{{ name }}>>> {
+    {{ name }}::find_all()
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+pub async fn get(Path(id): Path<i32>) -> HandlerResult<Json<{{ name }}>> {
+    {{ name }}::find_by_id(id)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::NOT_FOUND, e))
+}
+
+pub async fn create(Json(input): Json<{{ name }}Input>) -> HandlerResult<(StatusCode, Json<{{ name }}>)> {
+    input
+        .validate()
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    let item = {{ name }}::from(input)
+        .create()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok((StatusCode::CREATED, Json(item)))
+}
+
+pub async fn update(
+    Path(id): Path<i32>,
+    Json(input): Json<Update{{ name }}Input>,
+) -> HandlerResult<Json<{{ name }}>> {
+    input
+        .validate()
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    let mut item = {{ name }}::find_by_id(id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    item.apply(input);
+    item.update()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(item))
+}
+
+pub async fn delete(Path(id): Path<i32>) -> HandlerResult<StatusCode> {
+    let item = {{ name }}::find_by_id(id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    item.delete()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
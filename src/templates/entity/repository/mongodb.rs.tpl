@@ -0,0 +1,80 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, Document},
+    options::ReturnDocument,
+    Collection,
+};
+
+use super::entity::{{ name }};
+use crate::database::database;
+
+impl {{ name }} {
+    fn collection() -> Collection<Self> {
+        database().collection("{{ collection }}")
+    }
+
+    /// Increments the counter of the collection in `counters`, so that ids
+    /// are never reused, even by concurrent inserts.
+    async fn next_id() -> Result<i32, String> {
+        let counter = database()
+            .collection::<Document>("counters")
+            .find_one_and_update(doc! { "_id": "{{ collection }}" }, doc! { "$inc": { "seq": 1 } })
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "the {{ collection }} counter was not created".to_string())?;
+        counter.get_i32("seq").map_err(|e| e.to_string())
+    }
+
+    pub async fn find_all() -> Result<Vec<Self>, String> {
+        Self::collection()
+            .find(doc! {})
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_by_id(id: i32) -> Result<Self, String> {
+        Self::collection()
+            .find_one(doc! { "id": id })
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("{{ name }} {} not found", id))
+    }
+
+    pub async fn create(&self) -> Result<Self, String> {
+        let mut item = self.clone();
+        item.id = Self::next_id().await?;
+        item.created_at = Some(Utc::now());
+        item.updated_at = item.created_at;
+
+        Self::collection()
+            .insert_one(&item)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(item)
+    }
+
+    pub async fn update(&self) -> Result<(), String> {
+        let mut item = self.clone();
+        item.updated_at = Some(Utc::now());
+
+        Self::collection()
+            .replace_one(doc! { "id": self.id }, &item)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn delete(&self) -> Result<(), String> {
+        Self::collection()
+            .delete_one(doc! { "id": self.id })
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,70 @@
+{% if columns %}
+{% set insert = "INSERT INTO " ~ table ~ " (" ~ columns|map(attribute="name")|join(", ") ~ ") VALUES (" ~ columns|map(attribute="placeholder")|join(", ") ~ ")" %}
+{% elif mysql %}
+{% set insert = "INSERT INTO " ~ table ~ " () VALUES ()" %}
+{% else %}
+{% set insert = "INSERT INTO " ~ table ~ " DEFAULT VALUES" %}
+{% endif %}
+use super::entity::{{ name }};
+use crate::database::pool;
+
+impl {{ name }} {
+    pub async fn find_all() -> Result<Vec<Self>, String> {
+        sqlx::query_as::<_, Self>("SELECT * FROM {{ table }} ORDER BY id")
+            .fetch_all(pool())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_by_id(id: i32) -> Result<Self, String> {
+        sqlx::query_as::<_, Self>("SELECT * FROM {{ table }} WHERE id = {{ first_placeholder }}")
+            .bind(id)
+            .fetch_optional(pool())
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("{{ name }} {} not found", id))
+    }
+
+    pub async fn create(&self) -> Result<Self, String> {
+{% if mysql %}
+        let result = sqlx::query("{{ insert }}")
+{% for column in columns %}
+            .bind({{ column.bind }})
+{% endfor %}
+            .execute(pool())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Self::find_by_id(result.last_insert_id() as i32).await
+{% else %}
+        sqlx::query_as::<_, Self>("{{ insert }} RETURNING *")
+{% for column in columns %}
+            .bind({{ column.bind }})
+{% endfor %}
+            .fetch_one(pool())
+            .await
+            .map_err(|e| e.to_string())
+{% endif %}
+    }
+
+    pub async fn update(&self) -> Result<(), String> {
+        sqlx::query("UPDATE {{ table }} SET {% for column in columns %}{{ column.name }} = {{ column.placeholder }}, {% endfor %}updated_at = CURRENT_TIMESTAMP WHERE id = {{ id_placeholder }}")
+{% for column in columns %}
+            .bind({{ column.bind }})
+{% endfor %}
+            .bind(self.id)
+            .execute(pool())
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn delete(&self) -> Result<(), String> {
+        sqlx::query("DELETE FROM {{ table }} WHERE id = {{ first_placeholder }}")
+            .bind(self.id)
+            .execute(pool())
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
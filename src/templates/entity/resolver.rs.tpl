@@ -0,0 +1,42 @@
+use async_graphql::{Object, Result};
+use validator::Validate;
+
+use super::entity::{ {{- name }}, {{ name }}Input, Update{{ name }}Input};
+
+#[derive(Default)]
+pub struct {{ name }}Query;
+
+#[Object]
+impl {{ name }}Query {
+    async fn {{ name|lower|plural }}(&self) -> Result<Vec<{{ name }}>> {
+        Ok({{ name }}::find_all().await?)
+    }
+
+    async fn {{ name|lower }}(&self, id: i32) -> Result<{{ name }}> {
+        Ok({{ name }}::find_by_id(id).await?)
+    }
+}
+
+#[derive(Default)]
+pub struct {{ name }}Mutation;
+
+#[Object]
+impl {{ name }}Mutation {
+    async fn create_{{ name|lower }}(&self, input: {{ name }}Input) -> Result<{{ name }}> {
+        input.validate()?;
+        Ok({{ name }}::from(input).create().await?)
+    }
+
+    async fn update_{{ name|lower }}(&self, id: i32, input: Update{{ name }}Input) -> Result<{{ name }}> {
+        input.validate()?;
+        let mut item = {{ name }}::find_by_id(id).await?;
+        item.apply(input);
+        item.update().await?;
+        Ok(item)
+    }
+
+    async fn delete_{{ name|lower }}(&self, id: i32) -> Result<bool> {
+        {{ name }}::find_by_id(id).await?.delete().await?;
+        Ok(true)
+    }
+}
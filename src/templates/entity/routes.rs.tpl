@@ -0,0 +1,14 @@
+use axum::{routing::get, Router};
+
+use super::handler;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(handler::list).post(handler::create))
+        .route(
+            "/{id}",
+            get(handler::get)
+                .put(handler::update)
+                .delete(handler::delete),
+        )
+}
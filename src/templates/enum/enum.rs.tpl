@@ -0,0 +1,6 @@
+use serde::{Deserialize, Serialize};
+{% if graphql %}
+use async_graphql::Enum;
+{% endif %}
+
+{% include "partials/enum.rs" %}
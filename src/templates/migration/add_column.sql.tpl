@@ -0,0 +1 @@
+ALTER TABLE {{ table }} ADD COLUMN {{ column }};
@@ -0,0 +1 @@
+CREATE UNIQUE INDEX {{ index }} ON {{ table }} ({{ columns|join(", ") }});
@@ -0,0 +1 @@
+CREATE TYPE {{ enum_type.type_name }} AS ENUM ({% for value in enum_type.values %}'{{ value }}'{{ ", " if not loop.last }}{% endfor %});
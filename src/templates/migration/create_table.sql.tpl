@@ -0,0 +1,11 @@
+{% for enum_type in enums %}
+{% include "migration/create_enum.sql" %}
+{% endfor %}
+{% if enums %}
+
+{% endif %}
+CREATE TABLE IF NOT EXISTS {{ table }} (
+{% for column in columns %}
+    {{ column }}{{ "," if not loop.last }}
+{% endfor %}
+);
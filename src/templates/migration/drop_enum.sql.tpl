@@ -0,0 +1 @@
+DROP TYPE IF EXISTS {{ enum_type.type_name }};
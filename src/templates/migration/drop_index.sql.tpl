@@ -0,0 +1,5 @@
+{% if mysql %}
+DROP INDEX {{ index }} ON {{ table }};
+{% else %}
+DROP INDEX IF EXISTS {{ index }};
+{% endif %}
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS {{ table }};
+{% for enum_type in enums %}
+{% include "migration/drop_enum.sql" %}
+{% endfor %}
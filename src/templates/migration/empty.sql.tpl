@@ -0,0 +1,2 @@
+-- {{ name }} ({{ direction }})
+-- Add migration script here
@@ -0,0 +1,15 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize{% if graphql %}, Enum{% endif %}{% if sql %}, sqlx::Type{% endif %})]
+#[serde(rename_all = "snake_case")]
+{% if postgres %}
+#[sqlx(type_name = "{{ enum_type.type_name }}", rename_all = "snake_case")]
+{% elif sql %}
+#[sqlx(rename_all = "snake_case")]
+{% endif %}
+pub enum {{ enum_type.name }} {
+{% for variant in enum_type.variants %}
+{% if loop.first %}
+    #[default]
+{% endif %}
+    {{ variant }},
+{% endfor %}
+}
@@ -0,0 +1,10 @@
+{% if field.validations %}
+    #[validate({{ field.validations|join(", ") }})]
+{% endif %}
+{% if field.default %}
+    #[serde(default = "default_{{ field.name }}")]
+{% endif %}
+{% if field.json and sql %}
+    #[sqlx(json{% if field.optional %}(nullable){% endif %})]
+{% endif %}
+    pub {{ field.name }}: {{ field.type }},
@@ -0,0 +1,9 @@
+[package]
+name = "{{ name }}"
+{% if workspace %}
+version.workspace = true
+edition.workspace = true
+{% else %}
+version = "0.1.0"
+edition = "2021"
+{% endif %}
@@ -0,0 +1,26 @@
+# {{ name }}
+
+## Prerequisites
+- Rust (latest stable)
+- {{ database }}
+- trunk and the wasm32-unknown-unknown target (if using frontend)
+
+## Quick Start
+
+### Installation
+```bash
+# Clone the repository
+git clone <repository-url>
+cd {{ name }}
+
+# Install dependencies
+cargo build
+
+# Set up environment variables
+cp .env.example .env
+
+# Run migrations
+cargo run -- db migrate
+
+# Start the server
+cargo run
@@ -0,0 +1,15 @@
+# Database Configuration
+{% for key, value in database_settings %}
+{{ key }}={{ value }}
+{% endfor %}
+
+# Api Configuration
+SERVER_HOST=127.0.0.1
+SERVER_PORT=8080
+API_VERSION=v1
+LOG_LEVEL=debug
+
+# Web Configuration
+APP_HOST=127.0.0.1
+APP_PORT=3000
+NODE_ENV=development
\ No newline at end of file
@@ -0,0 +1,66 @@
+# Dependencies
+/node_modules
+/target
+/dist
+/build
+/.pnp
+.pnp.js
+cargo.lock
+
+# Testing
+/coverage
+
+# Production
+/build
+
+# Environment Variables
+.env
+.env.*
+!.env.example
+
+# IDE - VSCode
+.vscode/*
+!.vscode/settings.json
+!.vscode/tasks.json
+!.vscode/launch.json
+!.vscode/extensions.json
+
+# IDE - IntelliJ
+.idea/
+*.iml
+*.iws
+
+# IDE - Vim
+*.swp
+*.swo
+
+# Logs
+logs
+*.log
+npm-debug.log*
+yarn-debug.log*
+yarn-error.log*
+
+# OS
+.DS_Store
+.DS_Store?
+._*
+.Spotlight-V100
+.Trashes
+ehthumbs.db
+Thumbs.db
+
+# Database
+*.sqlite
+*.sqlite3
+*.db
+/database
+
+# Rust specific
+debug/
+target/
+**/*.rs.bk
+Cargo.lock
+
+# Generated documentation
+/doc
\ No newline at end of file
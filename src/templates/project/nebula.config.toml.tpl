@@ -0,0 +1,44 @@
+[project]
+name = "{{ name }}"
+type = "{{ project_type }}"  # web, api, full
+database = "{{ database }}"  # postgresql, mysql, mariadb, sqlite, mongodb
+server_type = "{{ server_type }}"  # rest, graphql, both
+frontend = "{{ frontend }}"  # leptos, yew
+
+[paths]
+src = "src"
+api = "{{ api_path }}"
+web = "{{ web_path }}"
+migrations = "migrations"
+templates = "templates"
+static = "static"
+tests = "tests"
+
+[environment]
+# Database Configuration
+{% for key, value in database_settings %}
+{{ key }} = "{{ value }}"
+{% endfor %}
+
+# Backend Configuration
+SERVER_HOST = "127.0.0.1"
+SERVER_PORT = "8080"
+API_VERSION = "v1"
+LOG_LEVEL = "debug"
+
+# Frontend Configuration
+APP_HOST = "127.0.0.1"
+APP_PORT = "3000"
+NODE_ENV = "development"
+
+[deployment]
+provider = "docker"
+container_name = "{{ name }}"
+docker_image = "rust:latest"
+docker_compose = true
+
+[security]
+jwt_enabled = true
+cors_enabled = true
+rate_limit = true
+allowed_origins = ["http://localhost:3000"]
\ No newline at end of file
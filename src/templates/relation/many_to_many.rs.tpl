@@ -0,0 +1,119 @@
+{% set target_id = target_column %}
+{% if endpoints and rest %}
+use axum::{extract::Path, http::StatusCode, Json};
+{% endif %}
+{% if endpoints and graphql %}
+use async_graphql::Object;
+{% endif %}
+{% if endpoints and (rest or graphql) %}
+
+{% endif %}
+use super::{{ name }};
+use crate::database::pool;
+use crate::{{ target|lower }}::{{ target }};
+
+/// {{ target|plural }} of a {{ name }}, linked through the `{{ join_table }}` table.
+impl {{ name }} {
+    pub async fn {{ field }}(&self) -> Result<Vec<{{ target }}>, String> {
+        sqlx::query_as::<_, {{ target }}>("SELECT t.* FROM {{ target_table }} t JOIN {{ join_table }} j ON j.{{ target_column }} = t.id WHERE j.{{ source_column }} = {{ first_placeholder }} ORDER BY t.id")
+            .bind(self.id)
+            .fetch_all(pool())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+{% if through %}
+    /// The other columns of `{{ join_table }}` take their default values.
+{% endif %}
+    pub async fn attach_{{ target|lower }}(&self, {{ target_id }}: i32) -> Result<(), String> {
+{% if mysql %}
+        sqlx::query("INSERT IGNORE INTO {{ join_table }} ({{ source_column }}, {{ target_column }}) VALUES (?, ?)")
+{% else %}
+        sqlx::query("INSERT INTO {{ join_table }} ({{ source_column }}, {{ target_column }}) VALUES ({{ first_placeholder }}, {{ second_placeholder }}) ON CONFLICT DO NOTHING")
+{% endif %}
+            .bind(self.id)
+            .bind({{ target_id }})
+            .execute(pool())
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn detach_{{ target|lower }}(&self, {{ target_id }}: i32) -> Result<(), String> {
+        sqlx::query("DELETE FROM {{ join_table }} WHERE {{ source_column }} = {{ first_placeholder }} AND {{ target_column }} = {{ second_placeholder }}")
+            .bind(self.id)
+            .bind({{ target_id }})
+            .execute(pool())
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+{% if endpoints and rest %}
+
+type HandlerResult<T> = Result<T, (StatusCode, String)>;
+
+pub async fn list(Path(id): Path<i32>) -> HandlerResult<Json<Vec<{{ target }}>>> {
+    let item = {{ name }}::find_by_id(id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    item.{{ field }}()
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+pub async fn attach(Path((id, {{ target_id }})): Path<(i32, i32)>) -> HandlerResult<StatusCode> {
+    let item = {{ name }}::find_by_id(id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    {{ target }}::find_by_id({{ target_id }})
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    item.attach_{{ target|lower }}({{ target_id }})
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn detach(Path((id, {{ target_id }})): Path<(i32, i32)>) -> HandlerResult<StatusCode> {
+    let item = {{ name }}::find_by_id(id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    item.detach_{{ target|lower }}({{ target_id }})
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+{% endif %}
+{% if endpoints and graphql %}
+
+#[derive(Default)]
+pub struct {{ name }}{{ field|pascal_case }}Query;
+
+#[Object]
+impl {{ name }}{{ field|pascal_case }}Query {
+    async fn {{ name|lower }}_{{ field }}(&self, id: i32) -> async_graphql::Result<Vec<{{ target }}>> {
+        Ok({{ name }}::find_by_id(id).await?.{{ field }}().await?)
+    }
+}
+
+#[derive(Default)]
+pub struct {{ name }}{{ field|pascal_case }}Mutation;
+
+#[Object]
+impl {{ name }}{{ field|pascal_case }}Mutation {
+    async fn attach_{{ name|lower }}_{{ target|lower }}(&self, {{ name|lower }}_id: i32, {{ target_id }}: i32) -> async_graphql::Result<bool> {
+        {{ target }}::find_by_id({{ target_id }}).await?;
+        {{ name }}::find_by_id({{ name|lower }}_id).await?.attach_{{ target|lower }}({{ target_id }}).await?;
+        Ok(true)
+    }
+
+    async fn detach_{{ name|lower }}_{{ target|lower }}(&self, {{ name|lower }}_id: i32, {{ target_id }}: i32) -> async_graphql::Result<bool> {
+        {{ name }}::find_by_id({{ name|lower }}_id).await?.detach_{{ target|lower }}({{ target_id }}).await?;
+        Ok(true)
+    }
+}
+{% endif %}
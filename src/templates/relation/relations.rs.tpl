@@ -0,0 +1,82 @@
+{% set loaded = relations|rejectattr("kind", "eq", "many_to_many")|list %}
+{% set queried = loaded|rejectattr("kind", "eq", "belongs_to")|list %}
+{% if graphql %}
+use async_graphql::ComplexObject;
+{% endif %}
+{% if queried and not sql %}
+{% if queried|selectattr("kind", "eq", "has_many")|list %}
+use futures::TryStreamExt;
+{% endif %}
+use mongodb::bson::doc;
+{% endif %}
+{% if graphql or (queried and not sql) %}
+
+{% endif %}
+{% set targets = relations|map(attribute="target")|unique|reject("eq", name)|sort %}
+use super::{{ name }};
+{% for target in targets if target|lower < "database" %}
+use crate::{{ target|lower }}::{{ target }};
+{% endfor %}
+{% if queried %}
+use crate::database::{% if sql %}pool{% else %}database{% endif %};
+{% endif %}
+{% for target in targets if target|lower >= "database" %}
+use crate::{{ target|lower }}::{{ target }};
+{% endfor %}
+{% if loaded %}
+
+/// Records related to a {{ name }}, loaded on demand.
+impl {{ name }} {
+{% for relation in loaded %}
+{% if not loop.first %}
+
+{% endif %}
+    pub async fn {{ relation.name }}(&self) -> Result<{{ relation.loaded_type }}, String> {
+{% if relation.kind == "belongs_to" and relation.optional %}
+        match self.{{ relation.foreign_key }} {
+            Some(id) => {{ relation.target }}::find_by_id(id).await.map(Some),
+            None => Ok(None),
+        }
+{% elif relation.kind == "belongs_to" %}
+        {{ relation.target }}::find_by_id(self.{{ relation.foreign_key }}).await
+{% elif sql %}
+        sqlx::query_as::<_, {{ relation.target }}>("SELECT * FROM {{ relation.target_table }} WHERE {{ relation.foreign_key }} = {{ first_placeholder }} ORDER BY id")
+            .bind(self.id)
+            .{% if relation.kind == "has_one" %}fetch_optional{% else %}fetch_all{% endif %}(pool())
+            .await
+            .map_err(|e| e.to_string())
+{% else %}
+        database()
+            .collection::<{{ relation.target }}>("{{ relation.target_table }}")
+{% if relation.kind == "has_one" %}
+            .find_one(doc! { "{{ relation.foreign_key }}": self.id })
+            .await
+            .map_err(|e| e.to_string())
+{% else %}
+            .find(doc! { "{{ relation.foreign_key }}": self.id })
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())
+{% endif %}
+{% endif %}
+    }
+{% endfor %}
+}
+{% endif %}
+{% if graphql %}
+
+#[ComplexObject]
+impl {{ name }} {
+{% for relation in relations %}
+{% if not loop.first %}
+
+{% endif %}
+    #[graphql(name = "{{ relation.name|camel_case }}")]
+    async fn resolve_{{ relation.name }}(&self) -> async_graphql::Result<{{ relation.loaded_type }}> {
+        Ok(self.{{ relation.name }}().await?)
+    }
+{% endfor %}
+}
+{% endif %}
@@ -0,0 +1,11 @@
+        .route(
+            "{{ base_path }}",
+            axum::routing::get({{ handler }}::list)
+                .post({{ handler }}::create),
+        )
+        .route(
+            "{{ base_path }}/{id}",
+            axum::routing::get({{ handler }}::get)
+                .put({{ handler }}::update)
+                .delete({{ handler }}::delete),
+        )
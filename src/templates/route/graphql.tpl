@@ -0,0 +1,4 @@
+        .route(
+            "/graphql",
+            axum::routing::get(crate::schema::graphiql).post(crate::schema::graphql_handler),
+        )
@@ -0,0 +1 @@
+        .nest("{{ path }}", crate::{{ module }}::routes::router())
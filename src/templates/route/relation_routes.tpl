@@ -0,0 +1,8 @@
+        .route(
+            "{{ base_path }}",
+            axum::routing::get({{ handler }}::list),
+        )
+        .route(
+            "{{ item_path }}",
+            axum::routing::post({{ handler }}::attach).delete({{ handler }}::detach),
+        )
@@ -0,0 +1,14 @@
+{% include "partials/package.toml" %}
+
+[dependencies]
+{% if frontend == "leptos" %}
+leptos = { version = "0.7", features = ["csr"] }
+leptos_router = "0.7"
+{% else %}
+yew = { version = "0.21", features = ["csr"] }
+yew-router = "0.18"
+{% endif %}
+console_error_panic_hook = "0.1"
+{% if workspace %}
+shared = {{ dependencies.shared }}
+{% endif %}
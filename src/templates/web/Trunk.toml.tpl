@@ -0,0 +1,7 @@
+[build]
+target = "index.html"
+dist = "dist"
+
+[serve]
+addresses = ["{{ host }}"]
+port = {{ port }}
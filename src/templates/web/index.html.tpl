@@ -0,0 +1,10 @@
+<!DOCTYPE html>
+<html lang="en">
+    <head>
+        <meta charset="utf-8" />
+        <meta name="viewport" content="width=device-width, initial-scale=1" />
+        <title>{{ name }}</title>
+        <link data-trunk rel="rust" data-wasm-opt="z" />
+    </head>
+    <body></body>
+</html>
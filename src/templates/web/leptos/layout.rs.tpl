@@ -0,0 +1,14 @@
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+#[component]
+pub fn Layout(children: Children) -> impl IntoView {
+    view! {
+        <header>
+            <nav>
+                <A href="/">"{{ name }}"</A>
+            </nav>
+        </header>
+        <main>{children()}</main>
+    }
+}
@@ -0,0 +1,6 @@
+use leptos::prelude::*;
+
+#[component]
+pub fn {{ component }}() -> impl IntoView {
+    view! { <h1>"{{ title }}"</h1> }
+}
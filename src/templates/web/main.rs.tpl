@@ -0,0 +1,12 @@
+mod app;
+mod components;
+mod pages;
+
+fn main() {
+    console_error_panic_hook::set_once();
+{% if frontend == "leptos" %}
+    leptos::mount::mount_to_body(app::App);
+{% else %}
+    yew::Renderer::<app::App>::new().render();
+{% endif %}
+}
@@ -0,0 +1,23 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::app::Route;
+
+#[derive(Properties, PartialEq)]
+pub struct LayoutProps {
+    pub children: Html,
+}
+
+#[function_component(Layout)]
+pub fn layout(props: &LayoutProps) -> Html {
+    html! {
+        <>
+            <header>
+                <nav>
+                    <Link<Route> to={Route::Home}>{"{{ name }}"}</Link<Route>>
+                </nav>
+            </header>
+            <main>{props.children.clone()}</main>
+        </>
+    }
+}
@@ -0,0 +1,6 @@
+use yew::prelude::*;
+
+#[function_component({{ component }})]
+pub fn {{ component|snake_case }}() -> Html {
+    html! { <h1>{"{{ title }}"}</h1> }
+}
@@ -0,0 +1,12 @@
+[workspace]
+resolver = "2"
+members = [{% for member in members %}"{{ member }}"{{ ", " if not loop.last }}{% endfor %}]
+
+[workspace.package]
+version = "0.1.0"
+edition = "2021"
+
+[workspace.dependencies]
+{% for name, spec in dependencies %}
+{{ name }} = {{ spec }}
+{% endfor %}
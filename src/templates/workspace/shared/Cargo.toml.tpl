@@ -0,0 +1,4 @@
+{% include "partials/package.toml" %}
+
+[dependencies]
+serde = {{ dependencies.serde }}
@@ -0,0 +1,326 @@
+use super::{errors::Error, prompt};
+use colored::Colorize;
+use similar::TextDiff;
+use std::collections::HashSet;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+static FORCE: AtomicBool = AtomicBool::new(false);
+static TRANSACTION: AtomicBool = AtomicBool::new(false);
+static CHANGES: Mutex<Vec<Change>> = Mutex::new(Vec::new());
+static OWNED: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Create,
+    Modify,
+    Overwrite,
+    Delete,
+}
+
+impl ChangeKind {
+    fn label(&self) -> &'static str {
+        match self {
+            ChangeKind::Create => "create",
+            ChangeKind::Modify => "modify",
+            ChangeKind::Overwrite => "overwrite",
+            ChangeKind::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conflict {
+    Overwrite,
+    Skip,
+    ShowDiff,
+    WriteNew,
+}
+
+/// A write recorded instead of being performed. `before` is the content the
+/// file had on disk when it was first touched, `after` its latest content,
+/// `None` once it is deleted.
+#[derive(Debug, Clone)]
+struct Change {
+    path: PathBuf,
+    kind: ChangeKind,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+pub fn enable_dry_run() {
+    DRY_RUN.store(true, Ordering::Relaxed);
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+pub fn enable_force() {
+    FORCE.store(true, Ordering::Relaxed);
+}
+
+pub fn is_forced() -> bool {
+    FORCE.load(Ordering::Relaxed)
+}
+
+/// Keeps every later write in memory until `commit`, so that a generation
+/// failing halfway leaves the project as it was.
+pub fn begin_transaction() {
+    TRANSACTION.store(true, Ordering::Relaxed);
+}
+
+pub fn in_transaction() -> bool {
+    TRANSACTION.load(Ordering::Relaxed)
+}
+
+/// Writes the changes kept since `begin_transaction` and returns the files
+/// written, with how each of them changed.
+pub fn commit() -> Result<Vec<(PathBuf, ChangeKind)>, Error> {
+    TRANSACTION.store(false, Ordering::Relaxed);
+    let changes = std::mem::take(&mut *CHANGES.lock().unwrap_or_else(|e| e.into_inner()));
+    for change in &changes {
+        match &change.after {
+            Some(after) => {
+                if let Some(parent) = change.path.parent() {
+                    fs::create_dir_all(parent).map_err(Error::FileSystem)?;
+                }
+                fs::write(&change.path, after).map_err(Error::FileSystem)?;
+            }
+            None if change.path.is_dir() => {
+                fs::remove_dir_all(&change.path).map_err(Error::FileSystem)?
+            }
+            None if change.path.exists() => {
+                fs::remove_file(&change.path).map_err(Error::FileSystem)?
+            }
+            None => {}
+        }
+    }
+    Ok(changes
+        .into_iter()
+        .map(|change| (change.path, change.kind))
+        .collect())
+}
+
+/// Runs `generate` in a transaction and writes its changes once it
+/// succeeds, returning the files written. `failure` says what was not done,
+/// as in "Post was not scaffolded". Under `--dry-run` nothing is kept and
+/// `None` is returned.
+pub fn transaction(
+    failure: &str,
+    generate: impl FnOnce() -> Result<(), Error>,
+) -> Result<Option<Vec<(PathBuf, ChangeKind)>>, Error> {
+    let dry_run = is_dry_run();
+    if !dry_run {
+        begin_transaction();
+    }
+    if let Err(e) = generate() {
+        if !dry_run {
+            eprintln!("⚠️  {}, no file was changed", failure);
+        }
+        return Err(e);
+    }
+    if dry_run {
+        return Ok(None);
+    }
+    commit().map(Some)
+}
+
+/// Marks `path` as created by this run, so that later steps may rewrite it
+/// without it being reported as a conflict.
+pub fn own(path: &Path) {
+    let mut owned = OWNED.lock().unwrap_or_else(|e| e.into_inner());
+    owned
+        .get_or_insert_with(HashSet::new)
+        .insert(path.to_path_buf());
+}
+
+pub fn is_owned(path: &Path) -> bool {
+    let owned = OWNED.lock().unwrap_or_else(|e| e.into_inner());
+    owned.as_ref().is_some_and(|owned| owned.contains(path))
+}
+
+/// Decides what to do with a generated file that would replace different
+/// content on disk. Without a terminal to ask on, the file is left alone and
+/// the generation fails.
+pub fn resolve_conflict(path: &Path, before: &str, after: &str) -> Result<Conflict, Error> {
+    if is_forced() {
+        return Ok(Conflict::Overwrite);
+    }
+
+    let path = display_path(path);
+    if !std::io::stdin().is_terminal() {
+        eprintln!(
+            "⚠️  {} has been modified, use --force to overwrite it",
+            path
+        );
+        return Err(Error::ElementAlreadyExists(path));
+    }
+
+    loop {
+        match prompt::ask_conflict_resolution(&path)? {
+            Conflict::ShowDiff => print_diff(&path, Some(before), Some(after)),
+            resolution => return Ok(resolution),
+        }
+    }
+}
+
+/// Records a write, merging it with an earlier change of the same file so
+/// the summary shows one diff per file.
+pub fn record(path: &Path, kind: ChangeKind, before: Option<String>, after: &str) {
+    let mut changes = CHANGES.lock().unwrap_or_else(|e| e.into_inner());
+    match changes.iter_mut().find(|change| change.path == path) {
+        Some(change) => {
+            // A file created by this run stays a creation whatever happens next
+            if change.kind == ChangeKind::Modify && kind == ChangeKind::Overwrite {
+                change.kind = kind;
+            }
+            if change.kind == ChangeKind::Delete {
+                change.kind = if change.before.is_some() {
+                    ChangeKind::Overwrite
+                } else {
+                    ChangeKind::Create
+                };
+            }
+            change.after = Some(after.to_string());
+        }
+        None => changes.push(Change {
+            path: path.to_path_buf(),
+            kind,
+            before,
+            after: Some(after.to_string()),
+        }),
+    }
+}
+
+/// Records the removal of a file or a directory. The changes recorded under
+/// it are dropped, and nothing is left to remove if this run created it.
+pub fn record_delete(path: &Path) {
+    let mut changes = CHANGES.lock().unwrap_or_else(|e| e.into_inner());
+    let before = match changes.iter().find(|change| change.path == path) {
+        Some(change) => change.before.clone(),
+        None => fs::read_to_string(path).ok(),
+    };
+    changes.retain(|change| !change.path.starts_with(path));
+    if path.exists() {
+        changes.push(Change {
+            path: path.to_path_buf(),
+            kind: ChangeKind::Delete,
+            before,
+            after: None,
+        });
+    }
+}
+
+/// Whether this run deleted `path` or a directory containing it.
+pub fn is_deleted(path: &Path) -> bool {
+    let changes = CHANGES.lock().unwrap_or_else(|e| e.into_inner());
+    changes
+        .iter()
+        .any(|change| change.kind == ChangeKind::Delete && path.starts_with(&change.path))
+}
+
+/// Latest recorded content of `path`, if this run wrote it.
+pub fn pending(path: &Path) -> Option<String> {
+    let changes = CHANGES.lock().unwrap_or_else(|e| e.into_inner());
+    changes
+        .iter()
+        .find(|change| change.path == path)
+        .and_then(|change| change.after.clone())
+}
+
+/// Paths written under `dir`, at any depth.
+pub fn pending_in(dir: &Path) -> Vec<PathBuf> {
+    let changes = CHANGES.lock().unwrap_or_else(|e| e.into_inner());
+    changes
+        .iter()
+        .filter(|change| change.after.is_some())
+        .filter(|change| change.path.starts_with(dir) && change.path != dir)
+        .map(|change| change.path.clone())
+        .collect()
+}
+
+pub fn print_summary() {
+    let changes = CHANGES.lock().unwrap_or_else(|e| e.into_inner());
+    if changes.is_empty() {
+        println!("\nDry run: nothing would be written");
+        return;
+    }
+
+    println!("\nDry run: no files were written\n");
+    let files = changes
+        .iter()
+        .map(|change| (change.path.clone(), change.kind))
+        .collect::<Vec<_>>();
+    print_files(&files);
+
+    // A deleted directory has no content to diff
+    for change in changes
+        .iter()
+        .filter(|change| change.before.is_some() || change.after.is_some())
+    {
+        println!();
+        print_diff(
+            &display_path(&change.path),
+            change.before.as_deref(),
+            change.after.as_deref(),
+        );
+    }
+}
+
+/// Lists `files` with how each of them changed.
+pub fn print_files(files: &[(PathBuf, ChangeKind)]) {
+    for (path, kind) in files {
+        let label = format!("{:<10}", kind.label());
+        let label = match kind {
+            ChangeKind::Create => label.green(),
+            ChangeKind::Modify => label.yellow(),
+            ChangeKind::Overwrite | ChangeKind::Delete => label.red(),
+        };
+        println!("  {} {}", label, display_path(path));
+    }
+}
+
+fn print_diff(path: &str, before: Option<&str>, after: Option<&str>) {
+    let old_header = match before {
+        Some(_) => format!("a/{}", path),
+        None => "/dev/null".to_string(),
+    };
+    let new_header = match after {
+        Some(_) => format!("b/{}", path),
+        None => "/dev/null".to_string(),
+    };
+
+    let diff = TextDiff::from_lines(before.unwrap_or_default(), after.unwrap_or_default());
+    let diff = diff
+        .unified_diff()
+        .header(&old_header, &new_header)
+        .to_string();
+
+    for line in diff.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            println!("{}", line.bold());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+fn display_path(path: &Path) -> String {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    path.strip_prefix(&cwd)
+        .or_else(|_| path.strip_prefix("."))
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
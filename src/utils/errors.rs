@@ -31,16 +31,13 @@ pub enum Error {
     #[error("Not a Nebula project directory")]
     NotNebulaProject,
 
-    #[error("Invalid project structure")]
-    InvalidProjectStructure,
-
     #[error("Invalid relation format: {0}")]
     InvalidRelationFormat(String),
 
     #[error("Invalid relation type: {0}")]
     InvalidRelationType(String),
 
-    #[error("Invalid field format: {0}. Expected format: name:type|validation1 validation2")]
+    #[error("Invalid field format: {0}")]
     InvalidFieldFormat(String),
 
     #[error("Missing type for field: {0}")]
@@ -1,9 +1,15 @@
-use super::errors::Error;
+use super::changes::{self, ChangeKind, Conflict};
+use super::{errors::Error, source};
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
 
 pub fn create_dir_if_not_exists(dir: &str) -> Result<(), Error> {
+    // Directories are implied by the files recorded under them
+    if changes::is_dry_run() || changes::in_transaction() {
+        return Ok(());
+    }
     if !Path::new(dir).exists() {
         fs::create_dir_all(dir).map_err(Error::FileSystem)?;
     }
@@ -11,58 +17,122 @@ pub fn create_dir_if_not_exists(dir: &str) -> Result<(), Error> {
 }
 
 pub fn write_file(path: &str, content: &str) -> Result<(), Error> {
-    let mut file = File::create(path).map_err(Error::FileSystem)?;
-    file.write_all(content.as_bytes())
-        .map_err(Error::FileSystem)?;
-    Ok(())
+    write(Path::new(path), content)
 }
 
-pub fn load_template(template_path: &str) -> Result<String, Error> {
-    fs::read_to_string(template_path).map_err(Error::FileSystem)
+/// Writes a generated file. Every write of the CLI goes through here or
+/// `update` so that `--dry-run` can record it. An existing file with other
+/// content is only replaced with `--force` or after asking the user.
+pub fn write(path: &Path, content: &str) -> Result<(), Error> {
+    apply(path, content, ChangeKind::Overwrite)
 }
 
-pub fn create_file_in_dir(dir: &str, filename: &str, content: &str) -> Result<(), Error> {
-    create_dir_if_not_exists(dir)?;
-    let file_path = Path::new(dir).join(filename);
-    write_file(file_path.to_str().unwrap(), content)?;
-    Ok(())
+/// Writes an edit of an existing file, such as a new mod declaration.
+pub fn update(path: &Path, content: &str) -> Result<(), Error> {
+    apply(path, content, ChangeKind::Modify)
 }
 
-pub fn init_file_from_template(
-    project_path: &Path,
-    file_path: &str,
-    template_path: &str,
-    replacements: Option<&[(&str, &str)]>,
-) -> Result<(), Error> {
-    // Get the executable path
-    let exe_path = std::env::current_exe()?;
-    let exe_dir = exe_path.parent().ok_or_else(|| Error::InvalidPath)?;
-    
-    let template_path: PathBuf = if Path::new(template_path).is_absolute() {
-        PathBuf::from(template_path)
-    } else {
-        // Look for templates relative to project root instead of executable
-        let project_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        project_root.join("src/templates").join(template_path)
-    };
+fn apply(path: &Path, content: &str, kind: ChangeKind) -> Result<(), Error> {
+    let before = read_to_string(path).ok();
+    if before.as_deref() == Some(content) {
+        return Ok(());
+    }
 
-    println!("🔍 Loading template from: {:?}", template_path);
+    if changes::is_dry_run() {
+        let kind = if before.is_some() {
+            kind
+        } else {
+            ChangeKind::Create
+        };
+        changes::record(path, kind, before, content);
+        return Ok(());
+    }
 
-    let content = load_template(template_path.to_str().ok_or_else(|| Error::InvalidPath)?)?;
+    let mut path = path.to_path_buf();
+    let conflict = kind == ChangeKind::Overwrite && !changes::is_owned(&path);
+    if let (true, Some(before)) = (conflict, &before) {
+        match changes::resolve_conflict(&path, before, content)? {
+            Conflict::Skip => {
+                println!("⏭️  Skipped {}", path.display());
+                return Ok(());
+            }
+            Conflict::WriteNew => {
+                let mut new_path = path.into_os_string();
+                new_path.push(".new");
+                path = PathBuf::from(new_path);
+                println!("📝 Wrote {}", path.display());
+            }
+            _ => {}
+        }
+    }
 
-    let content = if let Some(r) = replacements {
-        r.iter()
-            .fold(content, |acc, (key, value)| acc.replace(key, value))
-    } else {
-        content
-    };
+    if changes::in_transaction() {
+        let before = read_to_string(&path).ok();
+        let kind = if before.is_some() {
+            kind
+        } else {
+            ChangeKind::Create
+        };
+        changes::record(&path, kind, before, content);
+        changes::own(&path);
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(Error::FileSystem)?;
+    }
+    let mut file = File::create(&path).map_err(Error::FileSystem)?;
+    file.write_all(content.as_bytes())
+        .map_err(Error::FileSystem)?;
+    changes::own(&path);
+    Ok(())
+}
+
+/// Reads a file as this run left it, including writes recorded by `--dry-run`.
+pub fn read_to_string(path: &Path) -> Result<String, Error> {
+    match changes::pending(path) {
+        Some(content) => Ok(content),
+        None if changes::is_deleted(path) => {
+            Err(Error::FileSystem(std::io::ErrorKind::NotFound.into()))
+        }
+        None => fs::read_to_string(path).map_err(Error::FileSystem),
+    }
+}
+
+pub fn exists(path: &Path) -> bool {
+    (path.exists() && !changes::is_deleted(path))
+        || changes::pending(path).is_some()
+        || !changes::pending_in(path).is_empty()
+}
 
-    let full_path = project_path.join(file_path);
-    if full_path.exists() {
-        fs::remove_file(&full_path)?;
+/// Direct children of `dir`, sorted, including those recorded by `--dry-run`.
+pub fn list_dir(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut entries = Vec::new();
+    if dir.is_dir() {
+        for entry in fs::read_dir(dir).map_err(Error::FileSystem)? {
+            entries.push(entry.map_err(Error::FileSystem)?.path());
+        }
+    }
+    for path in changes::pending_in(dir) {
+        let child = path
+            .strip_prefix(dir)
+            .ok()
+            .and_then(|rest| rest.components().next())
+            .map(|component| dir.join(component));
+        if let Some(child) = child {
+            entries.push(child);
+        }
     }
+    entries.retain(|entry| exists(entry));
+    entries.sort();
+    entries.dedup();
+    Ok(entries)
+}
 
-    fs::write(&full_path, content)?;
+pub fn create_file_in_dir(dir: &str, filename: &str, content: &str) -> Result<(), Error> {
+    create_dir_if_not_exists(dir)?;
+    let file_path = Path::new(dir).join(filename);
+    write_file(file_path.to_str().unwrap(), content)?;
     Ok(())
 }
 
@@ -93,7 +163,17 @@ pub fn ensure_directory(path: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Removes a file or a directory. Like a write, the removal is only recorded
+/// by `--dry-run` and until the end of a transaction.
 pub fn remove_if_exists(path: &Path) -> Result<(), Error> {
+    if !exists(path) {
+        return Ok(());
+    }
+    if changes::is_dry_run() || changes::in_transaction() {
+        changes::record_delete(path);
+        return Ok(());
+    }
+
     if path.exists() {
         if path.is_dir() {
             fs::remove_dir_all(path).map_err(Error::FileSystem)?;
@@ -103,3 +183,53 @@ pub fn remove_if_exists(path: &Path) -> Result<(), Error> {
     }
     Ok(())
 }
+
+pub fn add_mod_declaration(path: &Path, declaration: &str) -> Result<(), Error> {
+    let content = read_to_string(path).unwrap_or_default();
+    let updated = source::add_mod_declaration(&content, declaration);
+    if updated != content {
+        update(path, &updated)?;
+    }
+    Ok(())
+}
+
+/// Removes `declaration` from a file, if the file has it.
+pub fn remove_mod_declaration(path: &Path, declaration: &str) -> Result<(), Error> {
+    let Ok(content) = read_to_string(path) else {
+        return Ok(());
+    };
+    let updated = source::remove_line(&content, declaration);
+    if updated != content {
+        update(path, &updated)?;
+    }
+    Ok(())
+}
+
+pub fn add_dependency(manifest: &Path, name: &str, spec: &str) -> Result<(), Error> {
+    let content = read_to_string(manifest)?;
+    let updated = source::add_dependency(&content, name, spec);
+    if updated != content {
+        update(manifest, &updated)?;
+    }
+    Ok(())
+}
+
+/// Reads `KEY=value` pairs from a dotenv file, ignoring comments and blank
+/// lines. A missing file yields an empty map.
+pub fn read_env(path: &Path) -> Result<BTreeMap<String, String>, Error> {
+    if !exists(path) {
+        return Ok(BTreeMap::new());
+    }
+
+    let content = read_to_string(path)?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim().trim_matches('"').trim_matches('\'');
+            (key.trim().to_string(), value.to_string())
+        })
+        .collect())
+}
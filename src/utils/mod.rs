@@ -1,4 +1,6 @@
+pub mod changes;
 pub mod errors;
 pub mod file;
 pub mod prompt;
+pub mod source;
 pub mod tools;
@@ -1,6 +1,7 @@
+use super::changes::Conflict;
 use super::errors::Error;
-use crate::types::ProjectType;
-use inquire::{Select, Text};
+use crate::types::{DatabaseType, FrontendType, ProjectType, ServerType};
+use inquire::{Confirm, Select, Text};
 
 pub fn ask_project_type() -> Result<ProjectType, Error> {
     const OPTIONS: &[&str] = &["web", "api", "full"];
@@ -8,37 +9,77 @@ pub fn ask_project_type() -> Result<ProjectType, Error> {
         .with_help_message("Choisissez le type de projet à créer")
         .prompt()
         .map_err(Error::InquireError)?
-        .parse()  // Assuming ProjectType implements FromStr
-}
-
-// pub fn ask_database_type() -> Result<Option<DatabaseType>, Error> {
-//     let options = vec!["mysql", "postgresql", "mariadb", "mongodb", "aucune"];
-//     let choice = Select::new(
-//         "Quel type de base de données voulez-vous utiliser ?",
-//         options,
-//     )
-//     .prompt()
-//     .map_err(|e| Error::InquireError(e))?;
-
-//     if choice == "aucune" {
-//         Ok(None)
-//     } else {
-//         Ok(Some(DatabaseType::from_str(&choice)?))
-//     }
-// }
-
-// pub fn ask_server_type() -> Result<Option<ServerType>, Error> {
-//     let options = vec!["rest", "graphql", "aucun"];
-//     let choice = Select::new("Quel type de serveur voulez-vous utiliser ?", options)
-//         .prompt()
-//         .map_err(|e| Error::InquireError(e))?;
-
-//     if choice == "aucun" {
-//         Ok(None)
-//     } else {
-//         Ok(Some(ServerType::from_str(&choice)?))
-//     }
-// }
+        .parse() // Assuming ProjectType implements FromStr
+}
+
+pub fn ask_database_type() -> Result<DatabaseType, Error> {
+    const OPTIONS: &[&str] = &["postgresql", "mysql", "mariadb", "sqlite", "mongodb"];
+    Select::new(
+        "Quel type de base de données voulez-vous utiliser ?",
+        OPTIONS.to_vec(),
+    )
+    .with_help_message("sqlite ne nécessite aucun serveur externe")
+    .prompt()
+    .map_err(Error::InquireError)?
+    .parse()
+}
+
+pub fn ask_server_type() -> Result<ServerType, Error> {
+    const OPTIONS: &[&str] = &["rest", "graphql", "both"];
+    Select::new(
+        "Quel type de serveur voulez-vous utiliser ?",
+        OPTIONS.to_vec(),
+    )
+    .with_help_message("both expose les routes REST et un endpoint /graphql")
+    .prompt()
+    .map_err(Error::InquireError)?
+    .parse()
+}
+
+pub fn ask_frontend_type() -> Result<FrontendType, Error> {
+    const OPTIONS: &[&str] = &["leptos", "yew"];
+    Select::new(
+        "Quel framework frontend voulez-vous utiliser ?",
+        OPTIONS.to_vec(),
+    )
+    .with_help_message("le client est compilé en WebAssembly et servi avec trunk")
+    .prompt()
+    .map_err(Error::InquireError)?
+    .parse()
+}
+
+pub fn ask_conflict_resolution(path: &str) -> Result<Conflict, Error> {
+    const OPTIONS: &[&str] = &["écraser", "ignorer", "afficher le diff", "écrire en .new"];
+    let choice = Select::new(
+        &format!("{} a été modifié, que voulez-vous faire ?", path),
+        OPTIONS.to_vec(),
+    )
+    .with_help_message("--force écrase tous les fichiers sans demander")
+    .prompt()
+    .map_err(Error::InquireError)?;
+
+    Ok(match choice {
+        "écraser" => Conflict::Overwrite,
+        "afficher le diff" => Conflict::ShowDiff,
+        "écrire en .new" => Conflict::WriteNew,
+        _ => Conflict::Skip,
+    })
+}
+
+pub fn ask_confirmation(message: &str) -> Result<bool, Error> {
+    Confirm::new(message)
+        .with_default(true)
+        .prompt()
+        .map_err(Error::InquireError)
+}
+
+/// Like `ask_confirmation`, for what cannot be undone: no by default.
+pub fn ask_removal_confirmation(message: &str) -> Result<bool, Error> {
+    Confirm::new(message)
+        .with_default(false)
+        .prompt()
+        .map_err(Error::InquireError)
+}
 
 pub fn ask_project_name() -> Result<String, Error> {
     Text::new("Nom du projet :")
@@ -0,0 +1,169 @@
+/// Returns the byte offsets of the opening and closing braces of the body of
+/// the first function whose declaration starts with `signature`.
+pub fn find_fn_body(content: &str, signature: &str) -> Option<(usize, usize)> {
+    let start = content.find(signature)?;
+    let open = start + content[start..].find('{')?;
+    let bytes = content.as_bytes();
+    let mut depth = 0;
+    let mut i = open;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((open, i));
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Adds `declaration` (e.g. `pub mod user;`) after the last top-level `mod`
+/// declaration, or at the top of the file if there is none.
+pub fn add_mod_declaration(content: &str, declaration: &str) -> String {
+    if content.lines().any(|l| l.trim() == declaration) {
+        return content.to_string();
+    }
+
+    let mut lines: Vec<&str> = content.lines().collect();
+    let position = lines
+        .iter()
+        .rposition(|l| l.starts_with("mod ") || l.starts_with("pub mod "))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    lines.insert(position, declaration);
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Removes the lines equal to `line` once trimmed, such as a declaration
+/// added by `add_mod_declaration`.
+pub fn remove_line(content: &str, line: &str) -> String {
+    if !content.lines().any(|l| l.trim() == line) {
+        return content.to_string();
+    }
+
+    let mut result = content
+        .lines()
+        .filter(|l| l.trim() != line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    result.push('\n');
+    result
+}
+
+/// Inserts `field`, one or more lines, at the end of `pub struct {name}`,
+/// before its timestamps if it has some.
+pub fn add_struct_field(content: &str, name: &str, field: &str) -> Option<String> {
+    let header = format!("pub struct {} {{", name);
+    let mut lines: Vec<&str> = content.lines().collect();
+    let start = lines.iter().position(|l| l.trim_start() == header)?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|l| l.trim_start().starts_with("pub created_at") || *l == "}")?;
+    lines.insert(end, field.trim_end());
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    Some(result)
+}
+
+/// Inserts `text`, one or more lines, before the first line equal to `end`
+/// after the line containing `start`.
+pub fn insert_before(content: &str, start: &str, end: &str, text: &str) -> Option<String> {
+    let mut lines: Vec<&str> = content.lines().collect();
+    let first = lines.iter().position(|l| l.contains(start))?;
+    let position = first + lines[first..].iter().position(|l| *l == end)?;
+    lines.insert(position, text.trim_end());
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    Some(result)
+}
+
+/// Names of the fields of `pub struct {name}`, with the attributes above
+/// each of them.
+pub fn struct_fields(content: &str, name: &str) -> Option<Vec<(String, Vec<String>)>> {
+    let header = format!("pub struct {} {{", name);
+    let mut lines = content.lines().map(str::trim);
+    lines.find(|l| *l == header)?;
+
+    let mut fields = Vec::new();
+    let mut attributes = Vec::new();
+    for line in lines.take_while(|l| *l != "}") {
+        if line.starts_with("#[") {
+            attributes.push(line.to_string());
+        } else if let Some((field, _)) = line.strip_prefix("pub ").and_then(|l| l.split_once(':')) {
+            fields.push((field.to_string(), std::mem::take(&mut attributes)));
+        }
+    }
+    Some(fields)
+}
+
+/// Returns the name and the variants of the first `pub enum` of `content`,
+/// as generated by `nebula generate enum`.
+pub fn find_enum(content: &str) -> Option<(String, Vec<String>)> {
+    let mut lines = content.lines().map(str::trim);
+    let name = lines.find_map(|l| l.strip_prefix("pub enum ")?.strip_suffix('{'))?;
+    let variants = lines
+        .take_while(|l| *l != "}")
+        .filter(|l| !l.is_empty() && !l.starts_with("#[") && !l.starts_with("//"))
+        .map(|l| l.trim_end_matches(',').to_string())
+        .collect();
+    Some((name.trim().to_string(), variants))
+}
+
+/// Adds `name = spec` at the end of the `[dependencies]` table of a
+/// Cargo.toml, unless `name` is already a dependency.
+pub fn add_dependency(content: &str, name: &str, spec: &str) -> String {
+    let mut lines: Vec<&str> = content.lines().collect();
+    let Some(table) = lines.iter().position(|l| l.trim() == "[dependencies]") else {
+        return content.to_string();
+    };
+
+    let end = lines[table + 1..]
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .map(|i| table + 1 + i)
+        .unwrap_or(lines.len());
+    let declared = lines[table + 1..end]
+        .iter()
+        .any(|l| l.split('=').next().is_some_and(|key| key.trim() == name));
+    if declared {
+        return content.to_string();
+    }
+
+    let position = lines[table + 1..end]
+        .iter()
+        .rposition(|l| !l.trim().is_empty())
+        .map(|i| table + 2 + i)
+        .unwrap_or(table + 1);
+    let dependency = format!("{} = {}", name, spec);
+    lines.insert(position, &dependency);
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
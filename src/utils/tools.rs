@@ -1,26 +1,36 @@
-use std::path::Path;
+use std::path::PathBuf;
 
-use crate::types::ProjectType;
+use crate::config::NebulaConfig;
 
 use super::errors::Error;
 
-pub fn check_is_nebula_project() -> Result<(), Error> {
-    if !Path::new("nebula.config.toml").exists() {
-        return Err(Error::NotNebulaProject);
+pub fn get_entity_dir(config: &NebulaConfig, name: &str) -> Result<PathBuf, Error> {
+    let entity_dir = config.api_src_path()?.join(name.to_lowercase());
+    if !super::file::exists(&entity_dir.join("entity.rs")) {
+        return Err(Error::ResourceNotFound(format!(
+            "entity '{}' does not exist, run `nebula generate entity {}` first",
+            name, name
+        )));
     }
-
-    Ok(())
+    Ok(entity_dir)
 }
 
-pub fn get_project_config() -> Result<ProjectType, Error> {
-    let is_src_present = Path::new("src").exists();
-    let is_web_present = Path::new("web").exists();
-    let is_api_present = Path::new("api").exists();
+pub fn pluralize(word: &str) -> String {
+    let ends_with_consonant_y = word.ends_with('y')
+        && !word
+            .chars()
+            .rev()
+            .nth(1)
+            .is_some_and(|c| "aeiou".contains(c));
 
-    match (is_src_present, is_web_present, is_api_present) {
-        (true, false, false) => Ok(ProjectType::Api),
-        (false, true, true) => Ok(ProjectType::Full),
-        (false, true, false) => Ok(ProjectType::Web),
-        _ => Err(Error::InvalidProjectStructure),
+    if ends_with_consonant_y {
+        format!("{}ies", &word[..word.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|s| word.ends_with(s))
+    {
+        format!("{}es", word)
+    } else {
+        format!("{}s", word)
     }
 }